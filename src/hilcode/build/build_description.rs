@@ -0,0 +1,648 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::build::build_graph::BuildGraph;
+use crate::hilcode::build::ninja_file::NinjaFile;
+use crate::hilcode::build::pool::PoolSet;
+use crate::hilcode::build::target::Target;
+use crate::hilcode::config::app_config::AppConfig;
+use crate::hilcode::config::variables::Variables;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::io::content_filter::ContentFilter;
+use crate::hilcode::io::file_filter::FileFilter;
+use crate::hilcode::io::file_filter::parse_age;
+use crate::hilcode::io::file_list::FileListSource;
+use crate::hilcode::io::file_set_expression::FileSetExpression;
+use crate::hilcode::io::file_set_glob::FileSetGlob;
+use crate::hilcode::io::file_set_registry::FileSetDefinition;
+use crate::hilcode::io::file_set_registry::FileSetRegistry;
+use crate::hilcode::io::file_stat::FileMode;
+use crate::hilcode::io::file_stat::FileSize;
+use crate::hilcode::parse::section_file::Entry;
+use crate::hilcode::parse::section_file::Section;
+use crate::hilcode::parse::section_file::SectionFile;
+
+pub const BUILD_FILE_NAME: &str = "hephaestus.build";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct BuildDescription
+{
+	pools: BTreeMap<String, usize>,
+	build_graph: BuildGraph,
+	file_sets: FileSetRegistry,
+}
+
+impl BuildDescription
+{
+	/// Loads the build description from `root_directory`, filling in the `variables` its values refer to; a missing
+	/// build file yields an empty description.
+	pub fn load(
+		root_directory: &Path,
+		variables: &Variables,
+	) -> Result<BuildDescription, HepheastusError>
+	{
+		let path: PathBuf = root_directory.join(BUILD_FILE_NAME);
+		if !path.is_file()
+		{
+			return Result::Ok(BuildDescription::default());
+		}
+		let section_file: SectionFile =
+			SectionFile::load(&path)?.map_values(|value: &str| variables.interpolate(value))?;
+		BuildDescription::from_section_file(&section_file)
+	}
+
+	pub fn from_section_file(section_file: &SectionFile) -> Result<BuildDescription, HepheastusError>
+	{
+		let mut pools: BTreeMap<String, usize> = BTreeMap::new();
+		for section in section_file.sections_of_kind("pool")
+		{
+			let (name, depth) = parse_pool(section_file.path(), section)?;
+			if pools.contains_key(&name)
+			{
+				return Result::Err(HepheastusError::parse_error(
+					section_file.path(),
+					section.line(),
+					format!("duplicate pool '{}'", name),
+				));
+			}
+			pools.insert(name, depth);
+		}
+		let directory: &Path = section_file.path().parent().unwrap_or(Path::new("."));
+		let mut build_graph: BuildGraph = BuildGraph::default();
+		for section in section_file.sections_of_kind("ninja")
+		{
+			let ninja_file: PathBuf = parse_ninja(section_file.path(), section)?;
+			build_graph.merge(NinjaFile::load(&directory.join(ninja_file))?)?;
+		}
+		let mut file_sets: FileSetRegistry = FileSetRegistry::default();
+		for section in section_file.sections_of_kind("file_set")
+		{
+			let (name, definition) = parse_file_set(section_file.path(), directory, section)?;
+			file_sets.define(name, definition);
+		}
+		Result::Ok(BuildDescription {
+			pools,
+			build_graph,
+			file_sets,
+		})
+	}
+
+	pub fn pools(&self) -> &BTreeMap<String, usize>
+	{
+		&self.pools
+	}
+
+	pub fn build_graph(&self) -> &BuildGraph
+	{
+		&self.build_graph
+	}
+
+	pub fn file_sets(&self) -> &FileSetRegistry
+	{
+		&self.file_sets
+	}
+
+	/// What can be asked for on the command line: the outputs of the targets, relative to `root_directory` when they
+	/// are below it, and `@name` for each file set.
+	pub fn target_names(
+		&self,
+		root_directory: &Path,
+	) -> Vec<String>
+	{
+		let outputs = self
+			.build_graph
+			.targets()
+			.flat_map(Target::outputs)
+			.map(|output: &PathBuf| {
+				output
+					.strip_prefix(root_directory)
+					.unwrap_or(output)
+					.display()
+					.to_string()
+			});
+		let file_sets = self.file_sets.names().map(|name: &str| format!("@{}", name));
+		outputs.chain(file_sets).collect()
+	}
+
+	/// Creates the pools declared here or in imported Ninja files, with depths from the configuration taking
+	/// precedence; fails when a target asks for a pool that none of them declares.
+	pub fn pool_set(
+		&self,
+		app_config: &AppConfig,
+	) -> Result<PoolSet, HepheastusError>
+	{
+		let mut depths: BTreeMap<String, usize> = self.build_graph.pools().clone();
+		depths.extend(self.pools.iter().map(|(name, depth)| (name.clone(), *depth)));
+		depths.extend(app_config.pools().iter().map(|(name, depth)| (name.clone(), *depth)));
+		let pool_set: PoolSet = PoolSet::new(app_config.jobs(), &depths);
+		for pool in self.build_graph.targets().filter_map(Target::pool)
+		{
+			pool_set.get(pool)?;
+		}
+		Result::Ok(pool_set)
+	}
+}
+
+fn parse_pool(
+	path: &Path,
+	section: &Section,
+) -> Result<(String, usize), HepheastusError>
+{
+	let name: String = section
+		.name()
+		.ok_or_else(|| HepheastusError::parse_error(path, section.line(), "a pool needs a name: '[pool name]'"))?
+		.to_string();
+	let entry: &Entry = section
+		.get("depth")
+		.ok_or_else(|| HepheastusError::parse_error(path, section.line(), format!("pool '{}' has no depth", name)))?;
+	let depth: usize = entry.value().parse().map_err(|_| {
+		HepheastusError::parse_error(
+			path,
+			entry.line(),
+			format!("pool depth must be a non-negative number, not '{}'", entry.value()),
+		)
+	})?;
+	Result::Ok((name, depth))
+}
+
+fn parse_ninja(
+	path: &Path,
+	section: &Section,
+) -> Result<PathBuf, HepheastusError>
+{
+	let entry: &Entry = section.get("file").ok_or_else(|| {
+		HepheastusError::parse_error(path, section.line(), "a ninja import needs a 'file = path/to/build.ninja'")
+	})?;
+	Result::Ok(PathBuf::from(entry.value()))
+}
+
+/// Parses `[file_set name]`, which either combines other sets with `set = @a - @b`, or searches with one or more
+/// `glob = pattern` entries below `base` (the build file's directory by default). A search takes the walk options
+/// `git_ignore`, `follow_links`, `hidden`, `one_file_system`, `directories`, `min_depth` and `max_depth`, as well as
+/// attribute and content filters.
+fn parse_file_set(
+	path: &Path,
+	directory: &Path,
+	section: &Section,
+) -> Result<(String, FileSetDefinition), HepheastusError>
+{
+	let name: String = section
+		.name()
+		.ok_or_else(|| {
+			HepheastusError::parse_error(path, section.line(), "a file set needs a name: '[file_set name]'")
+		})?
+		.to_string();
+	let globs: Vec<String> = section
+		.entries()
+		.filter(|entry: &&Entry| entry.key() == "glob")
+		.map(|entry: &Entry| entry.value().to_string())
+		.collect();
+	let sources: usize = [
+		section.get("set").is_some(),
+		!globs.is_empty(),
+		section.get("git_files").is_some(),
+		section.get("list").is_some(),
+	]
+	.iter()
+	.filter(|present: &&bool| **present)
+	.count();
+	if sources != 1
+	{
+		return Result::Err(HepheastusError::parse_error(
+			path,
+			section.line(),
+			format!(
+				"file set '{}' needs exactly one of 'set = ...', 'glob = ...', 'git_files = ...' or 'list = ...'",
+				name
+			),
+		));
+	}
+	let base_directory: PathBuf = match section.get("base")
+	{
+		Option::Some(entry) => directory.join(entry.value()),
+		Option::None => directory.to_path_buf(),
+	};
+	if let Option::Some(entry) = section.get("set")
+	{
+		let expression: FileSetExpression = FileSetExpression::parse(path, entry.line(), entry.value())?;
+		return Result::Ok((name, FileSetDefinition::Expression(expression)));
+	}
+	if let Option::Some(entry) = section.get("git_files")
+	{
+		let file_list_source: FileListSource = FileListSource::GitIndex(directory.join(entry.value()));
+		return Result::Ok((name, FileSetDefinition::List(file_list_source)));
+	}
+	if let Option::Some(entry) = section.get("list")
+	{
+		let file_list_source: FileListSource = match entry.value()
+		{
+			"-" => FileListSource::Stdin(base_directory),
+			list_file =>
+			{
+				FileListSource::ListFile {
+					path: directory.join(list_file),
+					base_directory,
+				}
+			}
+		};
+		return Result::Ok((name, FileSetDefinition::List(file_list_source)));
+	}
+	let mut file_set_glob: FileSetGlob = FileSetGlob::new(base_directory, globs)
+		.with_git_ignore(parse_flag(path, section, "git_ignore", false)?)
+		.with_follow_links(parse_flag(path, section, "follow_links", false)?)
+		.with_hidden(parse_flag(path, section, "hidden", true)?)
+		.with_one_file_system(parse_flag(path, section, "one_file_system", false)?)
+		.with_directories(parse_flag(path, section, "directories", true)?)
+		.with_depth(parse_depth(path, section, "min_depth", 0)?, parse_depth(path, section, "max_depth", usize::MAX)?);
+	for filter in parse_file_filters(path, section)?
+	{
+		file_set_glob = file_set_glob.with_filter(filter);
+	}
+	for content_filter in parse_content_filters(path, section)?
+	{
+		file_set_glob = file_set_glob.with_content_filter(content_filter);
+	}
+	Result::Ok((name, FileSetDefinition::Glob(file_set_glob)))
+}
+
+fn parse_flag(
+	path: &Path,
+	section: &Section,
+	key: &str,
+	default: bool,
+) -> Result<bool, HepheastusError>
+{
+	match section.get(key)
+	{
+		Option::Some(entry) =>
+		{
+			entry.value().parse().map_err(|_| {
+				HepheastusError::parse_error(
+					path,
+					entry.line(),
+					format!("{} must be 'true' or 'false', not '{}'", key, entry.value()),
+				)
+			})
+		}
+		Option::None => Result::Ok(default),
+	}
+}
+
+fn parse_depth(
+	path: &Path,
+	section: &Section,
+	key: &str,
+	default: usize,
+) -> Result<usize, HepheastusError>
+{
+	match section.get(key)
+	{
+		Option::Some(entry) =>
+		{
+			entry.value().parse().map_err(|_| {
+				HepheastusError::parse_error(
+					path,
+					entry.line(),
+					format!("{} must be a non-negative number, not '{}'", key, entry.value()),
+				)
+			})
+		}
+		Option::None => Result::Ok(default),
+	}
+}
+
+/// Parses the attribute filters of a `[file_set name]`: `type = regular|directory|symlink|executable`,
+/// `min_size = 1MiB`, `max_size = 10k`, `newer_than = 1d`, `older_than = 2w` and `mode = 755` (octal bits that must
+/// all be set).
+fn parse_file_filters(
+	path: &Path,
+	section: &Section,
+) -> Result<Vec<FileFilter>, HepheastusError>
+{
+	let mut filters: Vec<FileFilter> = Vec::new();
+	for entry in section.entries()
+	{
+		let value: &str = entry.value();
+		let filter: Result<FileFilter, String> = match entry.key()
+		{
+			"type" => value.parse().map(FileFilter::Kind),
+			"min_size" =>
+			{
+				value.parse().map(|min: FileSize| {
+					FileFilter::Size {
+						min: Option::Some(min),
+						max: Option::None,
+					}
+				})
+			}
+			"max_size" =>
+			{
+				value.parse().map(|max: FileSize| {
+					FileFilter::Size {
+						min: Option::None,
+						max: Option::Some(max),
+					}
+				})
+			}
+			"newer_than" => parse_age(value).map(FileFilter::modified_within),
+			"older_than" => parse_age(value).map(FileFilter::modified_before),
+			"mode" =>
+			{
+				u32::from_str_radix(value.trim_start_matches("0o"), 8)
+					.map(|bits: u32| {
+						FileFilter::Mode {
+							set: FileMode::new(bits),
+							clear: FileMode::new(0),
+						}
+					})
+					.map_err(|_| format!("mode must be octal permission bits such as 755, not '{}'", value))
+			}
+			_ => continue,
+		};
+		filters.push(filter.map_err(|message: String| HepheastusError::parse_error(path, entry.line(), message))?);
+	}
+	Result::Ok(filters)
+}
+
+/// Parses the content filters of a `[file_set name]`: `contains = text`, `matches = regex`, `starts_with = text` and
+/// `content = text|binary`.
+fn parse_content_filters(
+	path: &Path,
+	section: &Section,
+) -> Result<Vec<ContentFilter>, HepheastusError>
+{
+	let mut content_filters: Vec<ContentFilter> = Vec::new();
+	for entry in section.entries()
+	{
+		let value: &str = entry.value();
+		let content_filter: ContentFilter = match entry.key()
+		{
+			"contains" => ContentFilter::contains(value),
+			"matches" =>
+			{
+				ContentFilter::matches(value).map_err(|error: HepheastusError| {
+					HepheastusError::parse_error(path, entry.line(), error.to_string())
+				})?
+			}
+			"starts_with" => ContentFilter::starts_with(value),
+			"content" if value == "text" => ContentFilter::Text,
+			"content" if value == "binary" => ContentFilter::Binary,
+			"content" =>
+			{
+				return Result::Err(HepheastusError::parse_error(
+					path,
+					entry.line(),
+					format!("content must be 'text' or 'binary', not '{}'", value),
+				));
+			}
+			_ => continue,
+		};
+		content_filters.push(content_filter);
+	}
+	Result::Ok(content_filters)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+	use std::sync::Arc;
+
+	use crate::hilcode::build::build_description::BUILD_FILE_NAME;
+	use crate::hilcode::build::build_description::BuildDescription;
+	use crate::hilcode::build::build_graph::BuildGraph;
+	use crate::hilcode::build::pool::PoolSet;
+	use crate::hilcode::build::target::Target;
+	use crate::hilcode::config::app_config::AppConfig;
+	use crate::hilcode::config::variables::Value;
+	use crate::hilcode::config::variables::Variables;
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::io::file_list::FileListSource;
+	use crate::hilcode::io::file_set::FileSet;
+	use crate::hilcode::io::file_set_registry::FileSetDefinition;
+	use crate::hilcode::parse::section_file::SectionFile;
+	use crate::hilcode::test::env::TestEnv;
+
+	#[test]
+	fn pools()
+	{
+		let text: &str = "[pool link]\ndepth = 2\n\n[pool test]\ndepth = 1\n";
+		let section_file: SectionFile = SectionFile::parse(Path::new("hephaestus.build"), text).unwrap();
+		let build_description: BuildDescription = BuildDescription::from_section_file(&section_file).unwrap();
+		assert_eq!(Some(&2), build_description.pools().get("link"));
+		assert_eq!(Some(&1), build_description.pools().get("test"));
+	}
+
+	#[test]
+	fn configuration_overrides_depth()
+	{
+		let text: &str = "[pool link]\ndepth = 2\n";
+		let section_file: SectionFile = SectionFile::parse(Path::new("hephaestus.build"), text).unwrap();
+		let build_description: BuildDescription = BuildDescription::from_section_file(&section_file).unwrap();
+		let app_config: Arc<AppConfig> = AppConfig::builder().with_jobs(8).with_pool("link", 3).build();
+		let pool_set: PoolSet = build_description.pool_set(&app_config).unwrap();
+		assert_eq!(8, pool_set.jobs().depth());
+		assert_eq!(3, pool_set.get("link").unwrap().depth());
+	}
+
+	#[test]
+	fn duplicate_pool()
+	{
+		let text: &str = "[pool link]\ndepth = 2\n\n[pool link]\ndepth = 1\n";
+		let section_file: SectionFile = SectionFile::parse(Path::new("hephaestus.build"), text).unwrap();
+		match BuildDescription::from_section_file(&section_file)
+		{
+			Result::Err(HepheastusError::ParseError { line, message, .. }) =>
+			{
+				assert_eq!(4, line);
+				assert_eq!("duplicate pool 'link'", message);
+			}
+			result => panic!("expected a duplicate pool, not {:?}", result),
+		}
+	}
+
+	#[test]
+	fn unknown_pool()
+	{
+		let mut build_graph: BuildGraph = BuildGraph::default();
+		build_graph
+			.add_target(
+				Target::new(Path::new("."), "link")
+					.with_outputs(vec![PathBuf::from("app")], Vec::new())
+					.with_pool(Option::Some("link".to_string())),
+			)
+			.unwrap();
+		let mut build_description: BuildDescription = BuildDescription {
+			build_graph,
+			..BuildDescription::default()
+		};
+		let app_config: Arc<AppConfig> = AppConfig::builder().build();
+		match build_description.pool_set(&app_config)
+		{
+			Result::Err(HepheastusError::UnknownPool(name)) => assert_eq!("link", name),
+			result => panic!("expected an unknown pool, not {:?}", result),
+		}
+		build_description.pools.insert("link".to_string(), 1);
+		assert!(build_description.pool_set(&app_config).is_ok());
+	}
+
+	#[test]
+	fn ninja_import()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::create_dir(root_directory.join("native")).unwrap();
+		std::fs::write(
+			root_directory.join("native/build.ninja"),
+			"pool link_pool\n  depth = 1\nrule cc\n  command = cc -c $in -o $out\nbuild foo.o: cc foo.c\n",
+		)
+		.unwrap();
+		std::fs::write(root_directory.join(BUILD_FILE_NAME), "[ninja native]\nfile = native/build.ninja\n").unwrap();
+		let build_description: BuildDescription =
+			BuildDescription::load(root_directory, &Variables::default()).unwrap();
+		let target: &Target = build_description
+			.build_graph()
+			.producer(&root_directory.join("native/foo.o"))
+			.unwrap();
+		assert_eq!(Some("cc -c foo.c -o foo.o"), target.command());
+		assert_eq!(root_directory.join("native"), target.directory());
+		assert_eq!(vec!["native/foo.o"], build_description.target_names(root_directory));
+		let app_config: Arc<AppConfig> = AppConfig::builder().build();
+		let pool_set: PoolSet = build_description.pool_set(&app_config).unwrap();
+		assert_eq!(1, pool_set.get("link_pool").unwrap().depth());
+	}
+
+	#[test]
+	fn file_sets()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::create_dir(root_directory.join("tests")).unwrap();
+		for file in ["main.rs", "tests/cli.rs", "README.md"]
+		{
+			std::fs::write(root_directory.join(file), "").unwrap();
+		}
+		std::fs::write(
+			root_directory.join(BUILD_FILE_NAME),
+			concat!(
+				"[file_set all]\nglob = **/*.rs\nglob = **/*.md\n\n",
+				"[file_set tests]\nbase = tests\nglob = **\n\n",
+				"[file_set sources]\nset = @all - @tests\n",
+			),
+		)
+		.unwrap();
+		let build_description: BuildDescription =
+			BuildDescription::load(root_directory, &Variables::default()).unwrap();
+		assert_eq!(vec!["@all", "@sources", "@tests"], build_description.target_names(root_directory));
+		let sources: FileSet = build_description.file_sets().resolve("sources").unwrap();
+		assert_eq!(
+			vec![root_directory.join("README.md"), root_directory.join("main.rs")],
+			sources.absolute_paths().collect::<Vec<PathBuf>>()
+		);
+	}
+
+	#[test]
+	fn file_set_filters()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::write(root_directory.join("large.bin"), vec![0u8; 2048]).unwrap();
+		std::fs::write(root_directory.join("small.bin"), vec![0u8; 16]).unwrap();
+		std::fs::write(root_directory.join("run.sh"), "#!/bin/sh\n").unwrap();
+		std::fs::write(
+			root_directory.join(BUILD_FILE_NAME),
+			concat!(
+				"[file_set large]\nglob = *.bin\ntype = regular\nmin_size = 1KiB\nnewer_than = 1d\n\n",
+				"[file_set scripts]\nglob = *\ncontent = text\nstarts_with = #!\n",
+			),
+		)
+		.unwrap();
+		let build_description: BuildDescription =
+			BuildDescription::load(root_directory, &Variables::default()).unwrap();
+		let large: FileSet = build_description.file_sets().resolve("large").unwrap();
+		assert_eq!(vec![PathBuf::from("large.bin")], large.iter().cloned().collect::<Vec<PathBuf>>());
+		let scripts: FileSet = build_description.file_sets().resolve("scripts").unwrap();
+		assert_eq!(vec![PathBuf::from("run.sh")], scripts.iter().cloned().collect::<Vec<PathBuf>>());
+		let text: &str = "[file_set large]\nglob = *.bin\nmin_size = lots\n";
+		let section_file: SectionFile = SectionFile::parse(Path::new("hephaestus.build"), text).unwrap();
+		assert!(BuildDescription::from_section_file(&section_file).is_err());
+	}
+
+	#[test]
+	fn file_set_lists()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::write(root_directory.join("files.txt"), "main.rs\nlib.rs\n").unwrap();
+		std::fs::write(
+			root_directory.join(BUILD_FILE_NAME),
+			"[file_set listed]\nlist = files.txt\nbase = src\n\n[file_set piped]\nlist = -\n\n[file_set tracked]\ngit_files = .\n",
+		)
+		.unwrap();
+		let build_description: BuildDescription =
+			BuildDescription::load(root_directory, &Variables::default()).unwrap();
+		let listed: FileSet = build_description.file_sets().resolve("listed").unwrap();
+		assert_eq!(
+			vec![root_directory.join("src/lib.rs"), root_directory.join("src/main.rs")],
+			listed.absolute_paths().collect::<Vec<PathBuf>>()
+		);
+		assert_eq!(
+			Option::Some(&FileSetDefinition::List(FileListSource::Stdin(root_directory.to_path_buf()))),
+			build_description.file_sets().get("piped")
+		);
+		assert!(build_description.file_sets().resolve("tracked").is_err());
+	}
+
+	#[test]
+	fn file_set_needs_set_or_globs()
+	{
+		let text: &str = "[file_set sources]\nset = @a\nglob = *.rs\n";
+		let section_file: SectionFile = SectionFile::parse(Path::new("hephaestus.build"), text).unwrap();
+		assert!(BuildDescription::from_section_file(&section_file).is_err());
+		let text: &str = "[file_set sources]\nglob = *.rs\nlist = files.txt\n";
+		let section_file: SectionFile = SectionFile::parse(Path::new("hephaestus.build"), text).unwrap();
+		assert!(BuildDescription::from_section_file(&section_file).is_err());
+		let text: &str = "[file_set sources]\nglob = *.rs\nhidden = no\n";
+		let section_file: SectionFile = SectionFile::parse(Path::new("hephaestus.build"), text).unwrap();
+		assert!(BuildDescription::from_section_file(&section_file).is_err());
+	}
+
+	#[test]
+	fn invalid_depth()
+	{
+		let text: &str = "[pool link]\ndepth = many\n";
+		let section_file: SectionFile = SectionFile::parse(Path::new("hephaestus.build"), text).unwrap();
+		assert!(BuildDescription::from_section_file(&section_file).is_err());
+	}
+
+	#[test]
+	fn variables()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::create_dir(root_directory.join("src")).unwrap();
+		std::fs::write(root_directory.join("src/main.rs"), "").unwrap();
+		std::fs::write(
+			root_directory.join(BUILD_FILE_NAME),
+			"[pool link]\ndepth = ${depth}\n\n[file_set sources]\nbase = ${root}/${source}\nglob = *.rs\n",
+		)
+		.unwrap();
+		let mut variables: Variables = Variables::builtin(root_directory, "debug");
+		variables.define("depth", Value::Integer(3));
+		variables.define("source", Value::String("src".to_string()));
+		let build_description: BuildDescription = BuildDescription::load(root_directory, &variables).unwrap();
+		assert_eq!(Some(&3), build_description.pools().get("link"));
+		let sources: FileSet = build_description.file_sets().resolve("sources").unwrap();
+		assert_eq!(vec![root_directory.join("src/main.rs")], sources.absolute_paths().collect::<Vec<PathBuf>>());
+		let error: HepheastusError = BuildDescription::load(root_directory, &Variables::default()).unwrap_err();
+		assert!(
+			matches!(&error, HepheastusError::ParseError { line: 2, message, .. } if message.contains("depth")),
+			"{}",
+			error
+		);
+	}
+}
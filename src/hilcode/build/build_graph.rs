@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::build::target::Target;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BuildGraph
+{
+	targets: Vec<Target>,
+	producers: BTreeMap<PathBuf, usize>,
+	pools: BTreeMap<String, usize>,
+	defaults: Vec<PathBuf>,
+}
+
+impl BuildGraph
+{
+	/// Adds `target`, failing if one of its outputs is already produced by another target.
+	pub fn add_target(
+		&mut self,
+		target: Target,
+	) -> Result<(), HepheastusError>
+	{
+		let index: usize = self.targets.len();
+		if let Option::Some(output) = target.all_outputs().find(|output| self.producers.contains_key(*output))
+		{
+			return Result::Err(HepheastusError::DuplicateOutput(output.clone()));
+		}
+		for output in target.all_outputs()
+		{
+			self.producers.insert(output.clone(), index);
+		}
+		self.targets.push(target);
+		Result::Ok(())
+	}
+
+	pub fn add_pool(
+		&mut self,
+		name: impl Into<String>,
+		depth: usize,
+	)
+	{
+		self.pools.insert(name.into(), depth);
+	}
+
+	pub fn add_default(
+		&mut self,
+		path: impl Into<PathBuf>,
+	)
+	{
+		self.defaults.push(path.into());
+	}
+
+	pub fn merge(
+		&mut self,
+		other: BuildGraph,
+	) -> Result<(), HepheastusError>
+	{
+		for target in other.targets
+		{
+			self.add_target(target)?;
+		}
+		self.pools.extend(other.pools);
+		self.defaults.extend(other.defaults);
+		Result::Ok(())
+	}
+
+	pub fn targets(&self) -> impl Iterator<Item = &Target>
+	{
+		self.targets.iter()
+	}
+
+	/// The target that produces `path`, if any.
+	pub fn producer(
+		&self,
+		path: &Path,
+	) -> Option<&Target>
+	{
+		self.producers.get(path).map(|index: &usize| &self.targets[*index])
+	}
+
+	pub fn pools(&self) -> &BTreeMap<String, usize>
+	{
+		&self.pools
+	}
+
+	pub fn defaults(&self) -> &[PathBuf]
+	{
+		&self.defaults
+	}
+}
@@ -0,0 +1,126 @@
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::fs::TryLockError;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+pub const LOCK_FILE_NAME: &str = "lock";
+
+/// An advisory lock on the state directory, so that two builds in the same root directory do not run at once.
+///
+/// The lock file holds the PID of the process owning the lock. The operating system releases the lock when that
+/// process exits, so a stale lock file never blocks a build.
+#[derive(Debug)]
+pub struct BuildLock
+{
+	path: PathBuf,
+	file: File,
+}
+
+impl BuildLock
+{
+	/// Takes the lock, failing with [`HepheastusError::BuildLocked`] if another build holds it.
+	pub fn try_acquire(state_directory: &Path) -> Result<BuildLock, HepheastusError>
+	{
+		let (path, file) = BuildLock::open(state_directory)?;
+		match file.try_lock()
+		{
+			Result::Ok(()) => BuildLock::locked(path, file),
+			Result::Err(TryLockError::WouldBlock) =>
+			{
+				let pid: Option<u32> = BuildLock::holder(state_directory);
+				Result::Err(HepheastusError::BuildLocked { path, pid })
+			}
+			Result::Err(TryLockError::Error(error)) => Result::Err(HepheastusError::IoError(error)),
+		}
+	}
+
+	/// Takes the lock, blocking until another build releases it.
+	pub fn acquire(state_directory: &Path) -> Result<BuildLock, HepheastusError>
+	{
+		let (path, file) = BuildLock::open(state_directory)?;
+		file.lock()?;
+		BuildLock::locked(path, file)
+	}
+
+	/// The PID written by the current holder of the lock, if any.
+	pub fn holder(state_directory: &Path) -> Option<u32>
+	{
+		std::fs::read_to_string(state_directory.join(LOCK_FILE_NAME))
+			.ok()
+			.and_then(|text: String| text.trim().parse().ok())
+	}
+
+	pub fn path(&self) -> &Path
+	{
+		&self.path
+	}
+
+	fn open(state_directory: &Path) -> Result<(PathBuf, File), HepheastusError>
+	{
+		std::fs::create_dir_all(state_directory)?;
+		let path: PathBuf = state_directory.join(LOCK_FILE_NAME);
+		let file: File = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.truncate(false)
+			.open(&path)?;
+		Result::Ok((path, file))
+	}
+
+	fn locked(
+		path: PathBuf,
+		mut file: File,
+	) -> Result<BuildLock, HepheastusError>
+	{
+		file.set_len(0)?;
+		file.seek(SeekFrom::Start(0))?;
+		writeln!(file, "{}", std::process::id())?;
+		file.flush()?;
+		Result::Ok(BuildLock { path, file })
+	}
+}
+
+impl Drop for BuildLock
+{
+	fn drop(&mut self)
+	{
+		let _ = self.file.set_len(0);
+		let _ = self.file.unlock();
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+
+	use crate::hilcode::build::build_lock::BuildLock;
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::test::env::TestEnv;
+
+	#[test]
+	fn second_lock_is_refused()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let state_directory: &Path = &test_env.root_directory().join(".hephaestus");
+		let build_lock: BuildLock = BuildLock::try_acquire(state_directory).unwrap();
+		assert_eq!(Some(std::process::id()), BuildLock::holder(state_directory));
+		match BuildLock::try_acquire(state_directory)
+		{
+			Result::Err(HepheastusError::BuildLocked { pid, .. }) => assert_eq!(Some(std::process::id()), pid),
+			_ => panic!("expected the build lock to be held"),
+		}
+		drop(build_lock);
+		assert_eq!(None, BuildLock::holder(state_directory));
+		assert!(BuildLock::try_acquire(state_directory).is_ok());
+	}
+}
@@ -0,0 +1,316 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::build::build_graph::BuildGraph;
+use crate::hilcode::build::target::Target;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+pub const COMPILE_COMMANDS_FILE_NAME: &str = "compile_commands.json";
+
+const COMPILERS: [&str; 10] = [
+	"cc", "c++", "gcc", "g++", "clang", "clang++", "clang-cl", "cl", "icc", "icpc",
+];
+
+const COMPILER_WRAPPERS: [&str; 3] = ["ccache", "sccache", "distcc"];
+
+const SOURCE_EXTENSIONS: [&str; 9] = ["c", "C", "cc", "cp", "cpp", "cxx", "c++", "m", "mm"];
+
+/// One entry of a Clang compilation database.
+///
+/// `directory` is absolute, as the compilation database format requires, so the database can be written anywhere;
+/// `file` and `output` are relative to `directory`, as the command itself sees them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompileCommand
+{
+	directory: PathBuf,
+	file: PathBuf,
+	output: Option<PathBuf>,
+	command: String,
+}
+
+impl CompileCommand
+{
+	pub fn directory(&self) -> &Path
+	{
+		&self.directory
+	}
+
+	pub fn file(&self) -> &Path
+	{
+		&self.file
+	}
+
+	pub fn output(&self) -> Option<&Path>
+	{
+		self.output.as_deref()
+	}
+
+	pub fn command(&self) -> &str
+	{
+		&self.command
+	}
+
+	fn from_target(
+		target: &Target,
+		root_directory: &Path,
+	) -> Option<CompileCommand>
+	{
+		let command: &str = target.command()?;
+		if !is_compiler_invocation(command)
+		{
+			return Option::None;
+		}
+		let source: &PathBuf = target.inputs().iter().find(|input: &&PathBuf| is_source_file(input))?;
+		let directory: PathBuf = root_directory.join(target.directory());
+		let file: PathBuf = relative_to(source, target.directory());
+		let output: Option<PathBuf> = target
+			.outputs()
+			.first()
+			.map(|output: &PathBuf| relative_to(output, target.directory()));
+		Option::Some(CompileCommand {
+			directory,
+			file,
+			output,
+			command: command.to_string(),
+		})
+	}
+
+	fn to_json(&self) -> Result<String, HepheastusError>
+	{
+		let mut json: String = String::from("  {\n");
+		json.push_str(&format!("    \"directory\": {},\n", json_string(path_text(&self.directory)?)));
+		json.push_str(&format!("    \"file\": {},\n", json_string(path_text(&self.file)?)));
+		if let Option::Some(output) = &self.output
+		{
+			json.push_str(&format!("    \"output\": {},\n", json_string(path_text(output)?)));
+		}
+		json.push_str(&format!("    \"command\": {}\n", json_string(&self.command)));
+		json.push_str("  }");
+		Result::Ok(json)
+	}
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompileCommands(Vec<CompileCommand>);
+
+impl CompileCommands
+{
+	/// Collects an entry for every target whose command runs a C or C++ compiler on a C or C++ source file.
+	pub fn from_build_graph(
+		build_graph: &BuildGraph,
+		root_directory: &Path,
+	) -> CompileCommands
+	{
+		CompileCommands(
+			build_graph
+				.targets()
+				.filter_map(|target: &Target| CompileCommand::from_target(target, root_directory))
+				.collect(),
+		)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &CompileCommand>
+	{
+		self.0.iter()
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.0.is_empty()
+	}
+
+	/// Fails for a path that is not valid UTF-8, which JSON cannot hold.
+	pub fn to_json(&self) -> Result<String, HepheastusError>
+	{
+		if self.0.is_empty()
+		{
+			return Result::Ok("[]\n".to_string());
+		}
+		let entries: Vec<String> = self
+			.0
+			.iter()
+			.map(CompileCommand::to_json)
+			.collect::<Result<Vec<String>, HepheastusError>>()?;
+		Result::Ok(format!("[\n{}\n]\n", entries.join(",\n")))
+	}
+
+	pub fn write(
+		&self,
+		path: &Path,
+	) -> Result<(), HepheastusError>
+	{
+		std::fs::write(path, self.to_json()?)?;
+		Result::Ok(())
+	}
+}
+
+/// Recognises `gcc`, `clang++`, `/usr/bin/c++`, `x86_64-linux-gnu-gcc-12` and the like, optionally behind
+/// `VAR=value` assignments and a compiler cache such as `ccache`.
+fn is_compiler_invocation(command: &str) -> bool
+{
+	let mut words = command
+		.split_whitespace()
+		.skip_while(|word: &&str| word.contains('=') && !word.starts_with('-'));
+	let mut program: Option<&str> = words.next();
+	if let Option::Some(wrapper) = program
+		&& COMPILER_WRAPPERS.contains(&program_name(wrapper))
+	{
+		program = words.next();
+	}
+	program.map(program_name).is_some_and(is_compiler_name)
+}
+
+fn program_name(word: &str) -> &str
+{
+	let word: &str = word.trim_matches(|character: char| character == '"' || character == '\'');
+	let name: &str = word.rsplit(['/', '\\']).next().unwrap_or(word);
+	name.strip_suffix(".exe").unwrap_or(name)
+}
+
+fn is_compiler_name(name: &str) -> bool
+{
+	let name: &str = name.trim_end_matches(|character: char| character.is_ascii_digit() || character == '.');
+	let name: &str = name.strip_suffix('-').unwrap_or(name);
+	COMPILERS
+		.iter()
+		.any(|compiler: &&str| name == *compiler || name.ends_with(&format!("-{}", compiler)))
+}
+
+fn is_source_file(path: &Path) -> bool
+{
+	path.extension()
+		.and_then(|extension| extension.to_str())
+		.is_some_and(|extension: &str| SOURCE_EXTENSIONS.contains(&extension))
+}
+
+fn relative_to(
+	path: &Path,
+	base: &Path,
+) -> PathBuf
+{
+	match path.strip_prefix(base)
+	{
+		Result::Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+		Result::Ok(relative) => relative.to_path_buf(),
+		Result::Err(_) => path.to_path_buf(),
+	}
+}
+
+fn path_text(path: &Path) -> Result<&str, HepheastusError>
+{
+	path.to_str()
+		.ok_or_else(|| HepheastusError::NonUtf8Path(path.to_path_buf()))
+}
+
+fn json_string(text: &str) -> String
+{
+	let mut json: String = String::with_capacity(text.len() + 2);
+	json.push('"');
+	for character in text.chars()
+	{
+		match character
+		{
+			'"' => json.push_str("\\\""),
+			'\\' => json.push_str("\\\\"),
+			'\n' => json.push_str("\\n"),
+			'\r' => json.push_str("\\r"),
+			'\t' => json.push_str("\\t"),
+			character if (character as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", character as u32)),
+			character => json.push(character),
+		}
+	}
+	json.push('"');
+	json
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::ffi::OsString;
+	use std::os::unix::ffi::OsStringExt;
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::build::build_graph::BuildGraph;
+	use crate::hilcode::build::compile_commands::CompileCommand;
+	use crate::hilcode::build::compile_commands::CompileCommands;
+	use crate::hilcode::build::compile_commands::is_compiler_invocation;
+	use crate::hilcode::build::ninja_file::NinjaFile;
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+	#[test]
+	fn compiler_invocations()
+	{
+		assert!(is_compiler_invocation("gcc -c foo.c -o foo.o"));
+		assert!(is_compiler_invocation("/usr/bin/c++ -O2 -c foo.cpp"));
+		assert!(is_compiler_invocation("ccache clang++-17 -c foo.cc"));
+		assert!(is_compiler_invocation("CCACHE_DIR=/tmp x86_64-linux-gnu-gcc-12 -c foo.c"));
+		assert!(!is_compiler_invocation("ld -o app foo.o"));
+		assert!(!is_compiler_invocation("cmake -E touch foo"));
+		assert!(!is_compiler_invocation("ar rcs libfoo.a foo.o"));
+	}
+
+	#[test]
+	fn from_build_graph()
+	{
+		let text: &str = "
+rule cxx
+  command = /usr/bin/c++ -Iinclude -c $in -o $out
+rule link
+  command = /usr/bin/c++ $in -o $out
+build src/main.o: cxx ../src/main.cpp
+build app: link src/main.o
+";
+		let build_graph: BuildGraph =
+			NinjaFile::parse(Path::new("/root/build"), Path::new("/root/build/build.ninja"), text).unwrap();
+		let compile_commands: CompileCommands = CompileCommands::from_build_graph(&build_graph, Path::new("/root"));
+		assert_eq!(1, compile_commands.len());
+		let compile_command: &CompileCommand = compile_commands.iter().next().unwrap();
+		assert_eq!(Path::new("/root/build"), compile_command.directory());
+		assert_eq!(Path::new("../src/main.cpp"), compile_command.file());
+		assert_eq!(Some(Path::new("src/main.o")), compile_command.output());
+		let expected: &str = r#"[
+  {
+    "directory": "/root/build",
+    "file": "../src/main.cpp",
+    "output": "src/main.o",
+    "command": "/usr/bin/c++ -Iinclude -c ../src/main.cpp -o src/main.o"
+  }
+]
+"#;
+		assert_eq!(expected, compile_commands.to_json().unwrap());
+	}
+
+	#[test]
+	fn relative_target_directories()
+	{
+		let text: &str = "rule cc\n  command = cc -c $in -o $out\nbuild main.o: cc main.c\n";
+		let build_graph: BuildGraph =
+			NinjaFile::parse(Path::new("native"), Path::new("native/build.ninja"), text).unwrap();
+		let compile_commands: CompileCommands = CompileCommands::from_build_graph(&build_graph, Path::new("/root"));
+		let compile_command: &CompileCommand = compile_commands.iter().next().unwrap();
+		assert_eq!(Path::new("/root/native"), compile_command.directory());
+		assert_eq!(Path::new("main.c"), compile_command.file());
+	}
+
+	#[test]
+	fn non_utf8_paths()
+	{
+		let directory: PathBuf = PathBuf::from(OsString::from_vec(b"/root/bu\xffild".to_vec()));
+		let text: &str = "rule cc\n  command = cc -c $in -o $out\nbuild main.o: cc main.c\n";
+		let build_graph: BuildGraph = NinjaFile::parse(&directory, &directory.join("build.ninja"), text).unwrap();
+		let compile_commands: CompileCommands = CompileCommands::from_build_graph(&build_graph, Path::new("/root"));
+		match compile_commands.to_json()
+		{
+			Result::Err(HepheastusError::NonUtf8Path(path)) => assert_eq!(directory, path),
+			result => panic!("expected a non-UTF-8 path, not {:?}", result),
+		}
+	}
+}
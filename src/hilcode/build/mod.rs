@@ -0,0 +1,8 @@
+pub mod build_description;
+pub mod build_graph;
+pub mod build_lock;
+pub mod compile_commands;
+pub mod ninja_file;
+pub mod pool;
+pub mod project;
+pub mod target;
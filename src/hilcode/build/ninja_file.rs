@@ -0,0 +1,921 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::iter::Peekable;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::Chars;
+
+use crate::hilcode::build::build_graph::BuildGraph;
+use crate::hilcode::build::pool::CONSOLE_POOL;
+use crate::hilcode::build::target::Target;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+const PHONY_RULE: &str = "phony";
+
+#[derive(Clone, Debug, PartialEq)]
+enum Piece
+{
+	Literal(String),
+	Variable(String),
+}
+
+/// A value as written in a Ninja file, with its `$variable` references not yet expanded.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct EvalString(Vec<Piece>);
+
+impl EvalString
+{
+	fn push_char(
+		&mut self,
+		character: char,
+	)
+	{
+		match self.0.last_mut()
+		{
+			Option::Some(Piece::Literal(literal)) => literal.push(character),
+			_ => self.0.push(Piece::Literal(character.to_string())),
+		}
+	}
+
+	fn push_variable(
+		&mut self,
+		name: String,
+	)
+	{
+		self.0.push(Piece::Variable(name));
+	}
+
+	fn is_empty(&self) -> bool
+	{
+		self.0.is_empty()
+	}
+
+	fn as_literal(&self) -> Option<&str>
+	{
+		match self.0.as_slice()
+		{
+			[Piece::Literal(literal)] => Option::Some(literal),
+			_ => Option::None,
+		}
+	}
+
+	fn evaluate(
+		&self,
+		lookup: &dyn Fn(&str) -> String,
+	) -> String
+	{
+		let Result::Ok(value) = self.try_evaluate(&|name: &str| Result::<String, Infallible>::Ok(lookup(name)));
+		value
+	}
+
+	/// Like [`evaluate`](EvalString::evaluate), for a lookup that can fail.
+	fn try_evaluate<E>(
+		&self,
+		lookup: &dyn Fn(&str) -> Result<String, E>,
+	) -> Result<String, E>
+	{
+		let mut value: String = String::new();
+		for piece in &self.0
+		{
+			match piece
+			{
+				Piece::Literal(literal) => value.push_str(literal),
+				Piece::Variable(name) => value.push_str(&lookup(name)?),
+			}
+		}
+		Result::Ok(value)
+	}
+}
+
+#[derive(Debug, PartialEq)]
+enum PathToken
+{
+	Path(EvalString),
+	Colon,
+	Pipe,
+	DoublePipe,
+	PipeAt,
+}
+
+/// A line with `$`-continuations joined; `number` is the line it started on.
+#[derive(Debug)]
+struct Line
+{
+	number: usize,
+	indented: bool,
+	text: String,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Rule
+{
+	bindings: BTreeMap<String, EvalString>,
+}
+
+#[derive(Debug, Default)]
+struct Scope
+{
+	variables: BTreeMap<String, String>,
+	rules: BTreeMap<String, Rule>,
+}
+
+/// Reads a `build.ninja` file, following `include` and `subninja`, into a [`BuildGraph`].
+///
+/// Paths are resolved against the directory of the top-level file, which is also where Ninja would run the commands.
+pub struct NinjaFile
+{
+	directory: PathBuf,
+	scopes: Vec<Scope>,
+	build_graph: BuildGraph,
+	/// The canonical paths of the files being parsed, outermost first, so that an include cycle fails instead of
+	/// overflowing the stack.
+	parsing: Vec<PathBuf>,
+}
+
+impl NinjaFile
+{
+	pub fn load(path: &Path) -> Result<BuildGraph, HepheastusError>
+	{
+		let directory: PathBuf = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+		let mut ninja_file: NinjaFile = NinjaFile::new(directory);
+		ninja_file.parsing.push(std::fs::canonicalize(path)?);
+		ninja_file.parse_file(path)?;
+		Result::Ok(ninja_file.build_graph)
+	}
+
+	pub fn parse(
+		directory: &Path,
+		path: &Path,
+		text: &str,
+	) -> Result<BuildGraph, HepheastusError>
+	{
+		let mut ninja_file: NinjaFile = NinjaFile::new(directory.to_path_buf());
+		ninja_file.parsing.extend(std::fs::canonicalize(path).ok());
+		ninja_file.parse_text(path, text)?;
+		Result::Ok(ninja_file.build_graph)
+	}
+
+	fn new(directory: PathBuf) -> NinjaFile
+	{
+		let mut root_scope: Scope = Scope::default();
+		root_scope.rules.insert(PHONY_RULE.to_string(), Rule::default());
+		NinjaFile {
+			directory,
+			scopes: vec![root_scope],
+			build_graph: BuildGraph::default(),
+			parsing: Vec::new(),
+		}
+	}
+
+	fn parse_file(
+		&mut self,
+		path: &Path,
+	) -> Result<(), HepheastusError>
+	{
+		let text: String = std::fs::read_to_string(path)?;
+		self.parse_text(path, &text)
+	}
+
+	/// Parses the file that an `include` or `subninja` names, failing with `error` if it is already being parsed.
+	fn parse_included(
+		&mut self,
+		path: &Path,
+		error: impl Fn(String) -> HepheastusError,
+	) -> Result<(), HepheastusError>
+	{
+		let canonical_path: PathBuf = std::fs::canonicalize(path)?;
+		if let Option::Some(index) = self
+			.parsing
+			.iter()
+			.position(|parsing: &PathBuf| parsing == &canonical_path)
+		{
+			let cycle: Vec<String> = self.parsing[index..]
+				.iter()
+				.chain([&canonical_path])
+				.map(|path: &PathBuf| path.display().to_string())
+				.collect();
+			return Result::Err(error(format!("include cycle: {}", cycle.join(" -> "))));
+		}
+		self.parsing.push(canonical_path);
+		let result: Result<(), HepheastusError> = self.parse_file(path);
+		self.parsing.pop();
+		result
+	}
+
+	fn parse_text(
+		&mut self,
+		path: &Path,
+		text: &str,
+	) -> Result<(), HepheastusError>
+	{
+		let lines: Vec<Line> = logical_lines(text);
+		let mut index: usize = 0;
+		while index < lines.len()
+		{
+			let line: &Line = &lines[index];
+			index += 1;
+			let bindings_end: usize = index + lines[index..].iter().take_while(|line: &&Line| line.indented).count();
+			let bindings: &[Line] = &lines[index..bindings_end];
+			let error =
+				|message: String| -> HepheastusError { HepheastusError::parse_error(path, line.number, message) };
+			if line.indented
+			{
+				return Result::Err(error("unexpected indentation".to_string()));
+			}
+			let (keyword, rest) = line.text.split_once(' ').unwrap_or((&line.text, ""));
+			let rest: &str = rest.trim_start();
+			match keyword
+			{
+				"rule" =>
+				{
+					self.parse_rule(rest, bindings).map_err(error)?;
+					index = bindings_end;
+				}
+				"build" =>
+				{
+					self.parse_build(rest, bindings).map_err(error)?;
+					index = bindings_end;
+				}
+				"pool" =>
+				{
+					self.parse_pool(rest, bindings).map_err(error)?;
+					index = bindings_end;
+				}
+				"default" =>
+				{
+					self.parse_default(rest).map_err(error)?;
+				}
+				"include" =>
+				{
+					let include: PathBuf = self.evaluate_path(rest).map_err(error)?;
+					self.parse_included(&include, error)?;
+				}
+				"subninja" =>
+				{
+					let subninja: PathBuf = self.evaluate_path(rest).map_err(error)?;
+					self.scopes.push(Scope::default());
+					let result: Result<(), HepheastusError> = self.parse_included(&subninja, error);
+					self.scopes.pop();
+					result?;
+				}
+				_ =>
+				{
+					let (name, value) = parse_binding(&line.text).map_err(error)?;
+					let value: String = value.evaluate(&|name: &str| self.lookup(name));
+					self.scopes.last_mut().unwrap().variables.insert(name, value);
+				}
+			}
+		}
+		Result::Ok(())
+	}
+
+	fn parse_rule(
+		&mut self,
+		name: &str,
+		bindings: &[Line],
+	) -> Result<(), String>
+	{
+		let name: String = parse_identifier(name)?;
+		let scope: &Scope = self.scopes.last().unwrap();
+		if scope.rules.contains_key(&name)
+		{
+			return Result::Err(format!("duplicate rule '{}'", name));
+		}
+		let mut rule: Rule = Rule::default();
+		for binding in bindings
+		{
+			let (key, value) = parse_binding(&binding.text)?;
+			rule.bindings.insert(key, value);
+		}
+		if !rule.bindings.contains_key("command")
+		{
+			return Result::Err(format!("rule '{}' has no command", name));
+		}
+		self.scopes.last_mut().unwrap().rules.insert(name, rule);
+		Result::Ok(())
+	}
+
+	fn parse_pool(
+		&mut self,
+		name: &str,
+		bindings: &[Line],
+	) -> Result<(), String>
+	{
+		let name: String = parse_identifier(name)?;
+		let mut depth: Option<usize> = Option::None;
+		for binding in bindings
+		{
+			let (key, value) = parse_binding(&binding.text)?;
+			if key != "depth"
+			{
+				return Result::Err(format!("unexpected variable '{}' in pool '{}'", key, name));
+			}
+			let value: String = value.evaluate(&|name: &str| self.lookup(name));
+			depth = Option::Some(value.parse().map_err(|_| format!("invalid pool depth '{}'", value))?);
+		}
+		let depth: usize = depth.ok_or_else(|| format!("pool '{}' has no depth", name))?;
+		self.build_graph.add_pool(name, depth);
+		Result::Ok(())
+	}
+
+	fn parse_default(
+		&mut self,
+		text: &str,
+	) -> Result<(), String>
+	{
+		for token in scan_paths(text)?
+		{
+			match token
+			{
+				PathToken::Path(path) =>
+				{
+					let path: String = path.evaluate(&|name: &str| self.lookup(name));
+					self.build_graph.add_default(self.directory.join(path));
+				}
+				_ => return Result::Err("unexpected separator in 'default'".to_string()),
+			}
+		}
+		Result::Ok(())
+	}
+
+	fn parse_build(
+		&mut self,
+		text: &str,
+		bindings: &[Line],
+	) -> Result<(), String>
+	{
+		let mut edge_variables: BTreeMap<String, String> = BTreeMap::new();
+		for binding in bindings
+		{
+			let (key, value) = parse_binding(&binding.text)?;
+			let value: String = value.evaluate(&|name: &str| self.lookup(name));
+			edge_variables.insert(key, value);
+		}
+		let edge_lookup =
+			|name: &str| -> String { edge_variables.get(name).cloned().unwrap_or_else(|| self.lookup(name)) };
+		let mut groups: Vec<Vec<String>> = vec![Vec::new(); 6];
+		let mut group: usize = 0;
+		let mut rule_name: Option<String> = Option::None;
+		for token in scan_paths(text)?
+		{
+			match (token, group)
+			{
+				(PathToken::Path(path), 2) if rule_name.is_none() =>
+				{
+					rule_name = Option::Some(
+						path.as_literal()
+							.ok_or_else(|| "the rule name must be a plain name".to_string())?
+							.to_string(),
+					);
+				}
+				(PathToken::Path(path), _) => groups[group].push(path.evaluate(&edge_lookup)),
+				(PathToken::Pipe, 0) => group = 1,
+				(PathToken::Colon, 0 | 1) => group = 2,
+				(PathToken::Pipe, 2) if rule_name.is_some() => group = 3,
+				(PathToken::DoublePipe, 2 | 3) if rule_name.is_some() => group = 4,
+				(PathToken::PipeAt, 2..=4) if rule_name.is_some() => group = 5,
+				(token, _) => return Result::Err(format!("unexpected {:?} in build statement", token)),
+			}
+		}
+		let rule_name: String = rule_name.ok_or_else(|| "build statement has no ':' and rule".to_string())?;
+		if groups[0].is_empty()
+		{
+			return Result::Err("build statement has no outputs".to_string());
+		}
+		let rule: Rule = self
+			.scopes
+			.iter()
+			.rev()
+			.find_map(|scope: &Scope| scope.rules.get(&rule_name))
+			.cloned()
+			.ok_or_else(|| format!("unknown rule '{}'", rule_name))?;
+		let [
+			outputs,
+			implicit_outputs,
+			inputs,
+			implicit_inputs,
+			order_only_inputs,
+			_validations,
+		] = <[Vec<String>; 6]>::try_from(groups).unwrap();
+		let variable = |name: &str| -> Result<Option<String>, String> {
+			let value: String = self.evaluate_rule_variable(&rule, name, &edge_variables, &outputs, &inputs, &[])?;
+			Result::Ok(Option::Some(value).filter(|value: &String| !value.is_empty()))
+		};
+		let command: Option<String> = if rule_name == PHONY_RULE
+		{
+			Option::None
+		}
+		else
+		{
+			variable("command")?
+		};
+		let pool: Option<String> = variable("pool")?;
+		if let Option::Some(pool) = &pool
+			&& pool != CONSOLE_POOL
+			&& !self.build_graph.pools().contains_key(pool)
+		{
+			return Result::Err(format!("unknown pool name '{}'", pool));
+		}
+		let target: Target = Target::new(&self.directory, &rule_name)
+			.with_outputs(self.join_all(&outputs), self.join_all(&implicit_outputs))
+			.with_inputs(self.join_all(&inputs), self.join_all(&implicit_inputs), self.join_all(&order_only_inputs))
+			.with_command(command)
+			.with_description(variable("description")?)
+			.with_depfile(variable("depfile")?.map(|depfile: String| self.directory.join(depfile)))
+			.with_pool(pool);
+		self.build_graph.add_target(target).map_err(|error: HepheastusError| {
+			match error
+			{
+				HepheastusError::DuplicateOutput(output) =>
+				{
+					format!("multiple rules generate '{}'", output.display())
+				}
+				error => error.to_string(),
+			}
+		})
+	}
+
+	/// Looks `name` up the way Ninja does for rule variables: `$in` and `$out` first, then the build statement's own
+	/// variables, then the rule's, then the enclosing scopes.
+	///
+	/// `expanding` holds the rule variables whose values refer to `name`, outermost first; a rule variable that refers
+	/// back to one of them is a cycle, which fails like it does in Ninja.
+	fn evaluate_rule_variable(
+		&self,
+		rule: &Rule,
+		name: &str,
+		edge_variables: &BTreeMap<String, String>,
+		outputs: &[String],
+		inputs: &[String],
+		expanding: &[&str],
+	) -> Result<String, String>
+	{
+		match name
+		{
+			"in" => return Result::Ok(join_escaped(inputs, " ")),
+			"in_newline" => return Result::Ok(join_escaped(inputs, "\n")),
+			"out" => return Result::Ok(join_escaped(outputs, " ")),
+			_ =>
+			{}
+		}
+		if let Option::Some(value) = edge_variables.get(name)
+		{
+			return Result::Ok(value.clone());
+		}
+		if let Option::Some(value) = rule.bindings.get(name)
+		{
+			if let Option::Some(start) = expanding.iter().position(|expanding: &&str| *expanding == name)
+			{
+				let cycle: Vec<&str> = expanding[start..].iter().copied().chain([name]).collect();
+				return Result::Err(format!("cycle in rule variables: {}", cycle.join(" -> ")));
+			}
+			let expanding: Vec<&str> = expanding.iter().copied().chain([name]).collect();
+			return value.try_evaluate(&|name: &str| {
+				self.evaluate_rule_variable(rule, name, edge_variables, outputs, inputs, &expanding)
+			});
+		}
+		Result::Ok(self.lookup(name))
+	}
+
+	fn lookup(
+		&self,
+		name: &str,
+	) -> String
+	{
+		self.scopes
+			.iter()
+			.rev()
+			.find_map(|scope: &Scope| scope.variables.get(name))
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	fn evaluate_path(
+		&self,
+		text: &str,
+	) -> Result<PathBuf, String>
+	{
+		match scan_paths(text)?.as_slice()
+		{
+			[PathToken::Path(path)] => Result::Ok(self.directory.join(path.evaluate(&|name: &str| self.lookup(name)))),
+			_ => Result::Err("expected a single path".to_string()),
+		}
+	}
+
+	fn join_all(
+		&self,
+		paths: &[String],
+	) -> Vec<PathBuf>
+	{
+		paths.iter().map(|path: &String| self.directory.join(path)).collect()
+	}
+}
+
+fn logical_lines(text: &str) -> Vec<Line>
+{
+	let mut lines: Vec<Line> = Vec::new();
+	let mut pending: Option<Line> = Option::None;
+	for (index, raw) in text.lines().enumerate()
+	{
+		let (content, continues) = strip_continuation(raw);
+		match pending.as_mut()
+		{
+			Option::Some(line) => line.text.push_str(content.trim_start()),
+			Option::None =>
+			{
+				let trimmed: &str = content.trim_start();
+				if trimmed.is_empty() || trimmed.starts_with('#')
+				{
+					continue;
+				}
+				pending = Option::Some(Line {
+					number: index + 1,
+					indented: trimmed.len() != content.len(),
+					text: trimmed.to_string(),
+				});
+			}
+		}
+		if !continues
+		{
+			lines.extend(pending.take());
+		}
+	}
+	lines.extend(pending);
+	lines
+}
+
+/// Strips a trailing `$` that continues the line; `$$` at the end of a line is an escaped dollar instead.
+fn strip_continuation(raw: &str) -> (&str, bool)
+{
+	let bytes: &[u8] = raw.as_bytes();
+	let mut index: usize = 0;
+	while index < bytes.len()
+	{
+		if bytes[index] == b'$'
+		{
+			if index + 1 == bytes.len()
+			{
+				return (&raw[..index], true);
+			}
+			index += 2;
+		}
+		else
+		{
+			index += 1;
+		}
+	}
+	(raw, false)
+}
+
+fn parse_identifier(text: &str) -> Result<String, String>
+{
+	let text: &str = text.trim();
+	if text.is_empty()
+		|| !text
+			.chars()
+			.all(|character: char| is_variable_character(character) || character == '.')
+	{
+		return Result::Err(format!("invalid name '{}'", text));
+	}
+	Result::Ok(text.to_string())
+}
+
+fn parse_binding(text: &str) -> Result<(String, EvalString), String>
+{
+	let (name, value) = text
+		.split_once('=')
+		.ok_or_else(|| format!("expected 'name = value', not '{}'", text))?;
+	let name: String = parse_identifier(name)?;
+	let value: EvalString = scan_value(value.trim_start())?;
+	Result::Ok((name, value))
+}
+
+fn is_variable_character(character: char) -> bool
+{
+	character.is_ascii_alphanumeric() || character == '_' || character == '-'
+}
+
+fn scan_dollar(
+	characters: &mut Peekable<Chars>,
+	eval_string: &mut EvalString,
+) -> Result<(), String>
+{
+	match characters.next()
+	{
+		Option::Some(character @ ('$' | ' ' | ':')) => eval_string.push_char(character),
+		Option::Some('{') =>
+		{
+			let mut name: String = String::new();
+			loop
+			{
+				match characters.next()
+				{
+					Option::Some('}') => break,
+					Option::Some(character) if is_variable_character(character) || character == '.' =>
+					{
+						name.push(character)
+					}
+					_ => return Result::Err("unterminated '${'".to_string()),
+				}
+			}
+			eval_string.push_variable(name);
+		}
+		Option::Some(character) if is_variable_character(character) =>
+		{
+			let mut name: String = character.to_string();
+			while let Option::Some(&character) = characters.peek()
+				&& is_variable_character(character)
+			{
+				name.push(character);
+				characters.next();
+			}
+			eval_string.push_variable(name);
+		}
+		Option::Some(character) => return Result::Err(format!("bad escape '${}'", character)),
+		Option::None => return Result::Err("unexpected end of line after '$'".to_string()),
+	}
+	Result::Ok(())
+}
+
+fn scan_value(text: &str) -> Result<EvalString, String>
+{
+	let mut eval_string: EvalString = EvalString::default();
+	let mut characters: Peekable<Chars> = text.chars().peekable();
+	while let Option::Some(character) = characters.next()
+	{
+		match character
+		{
+			'$' => scan_dollar(&mut characters, &mut eval_string)?,
+			_ => eval_string.push_char(character),
+		}
+	}
+	Result::Ok(eval_string)
+}
+
+fn scan_paths(text: &str) -> Result<Vec<PathToken>, String>
+{
+	let mut tokens: Vec<PathToken> = Vec::new();
+	let mut current: EvalString = EvalString::default();
+	let mut characters: Peekable<Chars> = text.chars().peekable();
+	let flush = |current: &mut EvalString, tokens: &mut Vec<PathToken>| {
+		if !current.is_empty()
+		{
+			tokens.push(PathToken::Path(std::mem::take(current)));
+		}
+	};
+	while let Option::Some(character) = characters.next()
+	{
+		match character
+		{
+			'$' => scan_dollar(&mut characters, &mut current)?,
+			' ' | '\t' => flush(&mut current, &mut tokens),
+			':' =>
+			{
+				flush(&mut current, &mut tokens);
+				tokens.push(PathToken::Colon);
+			}
+			'|' =>
+			{
+				flush(&mut current, &mut tokens);
+				match characters.peek()
+				{
+					Option::Some('|') =>
+					{
+						characters.next();
+						tokens.push(PathToken::DoublePipe);
+					}
+					Option::Some('@') =>
+					{
+						characters.next();
+						tokens.push(PathToken::PipeAt);
+					}
+					_ => tokens.push(PathToken::Pipe),
+				}
+			}
+			_ => current.push_char(character),
+		}
+	}
+	flush(&mut current, &mut tokens);
+	Result::Ok(tokens)
+}
+
+/// Joins paths the way Ninja expands `$in` and `$out`, quoting those the shell would otherwise split.
+fn join_escaped(
+	paths: &[String],
+	separator: &str,
+) -> String
+{
+	paths
+		.iter()
+		.map(|path: &String| shell_escape(path))
+		.collect::<Vec<String>>()
+		.join(separator)
+}
+
+fn shell_escape(text: &str) -> String
+{
+	let is_safe = |character: char| -> bool { character.is_ascii_alphanumeric() || "_+-./=:@%,".contains(character) };
+	if !text.is_empty() && text.chars().all(is_safe)
+	{
+		return text.to_string();
+	}
+	format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::build::build_graph::BuildGraph;
+	use crate::hilcode::build::ninja_file::NinjaFile;
+	use crate::hilcode::build::target::Target;
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::test::env::TestEnv;
+
+	fn parse(text: &str) -> BuildGraph
+	{
+		NinjaFile::parse(Path::new("out"), Path::new("out/build.ninja"), text).unwrap()
+	}
+
+	#[test]
+	fn rule_and_build()
+	{
+		let build_graph: BuildGraph = parse(
+			"
+cflags = -O2
+rule cc
+  command = gcc $cflags -c $in -o $out
+  description = CC $out
+  depfile = $out.d
+
+build foo.o: cc foo.c | foo.h || gen
+  cflags = -O0 -g
+",
+		);
+		let target: &Target = build_graph.producer(Path::new("out/foo.o")).unwrap();
+		assert_eq!("cc", target.rule());
+		assert_eq!(Some("gcc -O0 -g -c foo.c -o foo.o"), target.command());
+		assert_eq!(Some("CC foo.o"), target.description());
+		assert_eq!(Some(Path::new("out/foo.o.d")), target.depfile());
+		assert_eq!(&[PathBuf::from("out/foo.c")], target.inputs());
+		assert_eq!(&[PathBuf::from("out/foo.h")], target.implicit_inputs());
+		assert_eq!(&[PathBuf::from("out/gen")], target.order_only_inputs());
+	}
+
+	#[test]
+	fn implicit_outputs_and_pools()
+	{
+		let build_graph: BuildGraph = parse(
+			"
+pool link_pool
+  depth = 2
+
+rule link
+  command = ld -o $out $in
+  pool = link_pool
+
+build app | app.map: link a.o b.o
+build all: phony app
+default all
+",
+		);
+		assert_eq!(Some(&2), build_graph.pools().get("link_pool"));
+		let target: &Target = build_graph.producer(Path::new("out/app.map")).unwrap();
+		assert_eq!(Some("ld -o app a.o b.o"), target.command());
+		assert_eq!(Some("link_pool"), target.pool());
+		let all: &Target = build_graph.producer(Path::new("out/all")).unwrap();
+		assert_eq!(None, all.command());
+		assert_eq!(&[PathBuf::from("out/all")], build_graph.defaults());
+	}
+
+	#[test]
+	fn unknown_pool()
+	{
+		let text: &str = "rule link\n  command = ld -o $out $in\n  pool = link_pool\nbuild app: link a.o\n";
+		match NinjaFile::parse(Path::new("out"), Path::new("out/build.ninja"), text)
+		{
+			Result::Err(HepheastusError::ParseError { line, message, .. }) =>
+			{
+				assert_eq!(4, line);
+				assert_eq!("unknown pool name 'link_pool'", message);
+			}
+			result => panic!("expected an unknown pool, not {:?}", result),
+		}
+		let text: &str = "rule run\n  command = ./$in\nbuild run: run app\n  pool = console\n";
+		let build_graph: BuildGraph = parse(text);
+		assert_eq!(Some("console"), build_graph.producer(Path::new("out/run")).unwrap().pool());
+	}
+
+	#[test]
+	fn rule_variable_cycles()
+	{
+		for (text, line, cycle) in [
+			("rule r\n  command = touch $command\nbuild a: r\n", 3, "command -> command"),
+			("rule r\n  command = $command $command\nbuild a: r\n", 3, "command -> command"),
+			(
+				"rule r\n  command = $flags\n  flags = -o $out $command\nbuild a: r\n",
+				4,
+				"command -> flags -> command",
+			),
+		]
+		{
+			match NinjaFile::parse(Path::new("out"), Path::new("out/build.ninja"), text)
+			{
+				Result::Err(HepheastusError::ParseError {
+					line: error_line,
+					message,
+					..
+				}) =>
+				{
+					assert_eq!(line, error_line);
+					assert_eq!(format!("cycle in rule variables: {}", cycle), message);
+				}
+				result => panic!("expected a cycle, not {:?}", result),
+			}
+		}
+		let text: &str = "rule r\n  command = $a $a\n  a = $b $b\n  b = x\nbuild a: r\n";
+		assert_eq!(Some("x x x x"), parse(text).producer(Path::new("out/a")).unwrap().command());
+	}
+
+	#[test]
+	fn escapes_and_continuations()
+	{
+		let build_graph: BuildGraph = parse(
+			"
+rule copy
+  command = cp $in $out && echo $$HOME $
+      done
+
+build my$ file.txt$:x: copy src.txt
+",
+		);
+		let target: &Target = build_graph.producer(Path::new("out/my file.txt:x")).unwrap();
+		assert_eq!(Some("cp src.txt 'my file.txt:x' && echo $HOME done"), target.command());
+	}
+
+	#[test]
+	fn unknown_rule()
+	{
+		let result = NinjaFile::parse(Path::new("out"), Path::new("out/build.ninja"), "build a: nope b\n");
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn duplicate_output()
+	{
+		let text: &str = "rule r\n  command = touch $out\nbuild a: r\nbuild a: r\n";
+		let result = NinjaFile::parse(Path::new("out"), Path::new("out/build.ninja"), text);
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn include_and_subninja()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::write(root_directory.join("rules.ninja"), "rule cc\n  command = cc $flags -c $in -o $out\n").unwrap();
+		std::fs::write(
+			root_directory.join("sub.ninja"),
+			"flags = -Wall\nrule cc\n  command = clang $flags -c $in -o $out\nbuild sub.o: cc sub.c\n",
+		)
+		.unwrap();
+		std::fs::write(
+			root_directory.join("build.ninja"),
+			"flags = -O2\ninclude rules.ninja\nsubninja sub.ninja\nbuild main.o: cc main.c\n",
+		)
+		.unwrap();
+		let build_graph: BuildGraph = NinjaFile::load(&root_directory.join("build.ninja")).unwrap();
+		let main: &Target = build_graph.producer(&root_directory.join("main.o")).unwrap();
+		assert_eq!(Some("cc -O2 -c main.c -o main.o"), main.command());
+		let sub: &Target = build_graph.producer(&root_directory.join("sub.o")).unwrap();
+		assert_eq!(Some("clang -Wall -c sub.c -o sub.o"), sub.command());
+	}
+
+	#[test]
+	fn include_cycle()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::write(root_directory.join("build.ninja"), "include rules.ninja\n").unwrap();
+		std::fs::write(root_directory.join("rules.ninja"), "rule cc\n  command = cc\n\nsubninja build.ninja\n")
+			.unwrap();
+		match NinjaFile::load(&root_directory.join("build.ninja"))
+		{
+			Result::Err(HepheastusError::ParseError { path, line, message }) =>
+			{
+				assert_eq!(root_directory.join("rules.ninja"), path);
+				assert_eq!(4, line);
+				assert!(message.starts_with("include cycle: "), "{}", message);
+				assert_eq!(2, message.matches("build.ninja").count(), "{}", message);
+				assert!(message.contains("rules.ninja ->"), "{}", message);
+			}
+			result => panic!("expected an include cycle, not {:?}", result),
+		}
+		std::fs::write(root_directory.join("self.ninja"), "include self.ninja\n").unwrap();
+		assert!(NinjaFile::load(&root_directory.join("self.ninja")).is_err());
+	}
+}
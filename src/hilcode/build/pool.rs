@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+/// The predefined pool for rules that need exclusive access to the terminal.
+pub const CONSOLE_POOL: &str = "console";
+
+/// Limits how many rules may run at the same time; a depth of `0` means unlimited.
+#[derive(Debug)]
+pub struct Pool
+{
+	name: String,
+	depth: usize,
+	in_use: Mutex<usize>,
+	released: Condvar,
+}
+
+impl Pool
+{
+	pub fn new(
+		name: impl Into<String>,
+		depth: usize,
+	) -> Pool
+	{
+		let name: String = name.into();
+		Pool {
+			name,
+			depth,
+			in_use: Mutex::new(0),
+			released: Condvar::new(),
+		}
+	}
+
+	pub fn name(&self) -> &str
+	{
+		&self.name
+	}
+
+	pub fn depth(&self) -> usize
+	{
+		self.depth
+	}
+
+	pub fn in_use(&self) -> usize
+	{
+		*self.in_use.lock().unwrap()
+	}
+
+	/// Blocks until a slot is free.
+	pub fn acquire(&self) -> PoolSlot<'_>
+	{
+		let mut in_use: MutexGuard<usize> = self.in_use.lock().unwrap();
+		while self.is_full(*in_use)
+		{
+			in_use = self.released.wait(in_use).unwrap();
+		}
+		*in_use += 1;
+		PoolSlot(self)
+	}
+
+	pub fn try_acquire(&self) -> Option<PoolSlot<'_>>
+	{
+		let mut in_use: MutexGuard<usize> = self.in_use.lock().unwrap();
+		if self.is_full(*in_use)
+		{
+			return Option::None;
+		}
+		*in_use += 1;
+		Option::Some(PoolSlot(self))
+	}
+
+	fn is_full(
+		&self,
+		in_use: usize,
+	) -> bool
+	{
+		self.depth != 0 && in_use >= self.depth
+	}
+
+	fn release(&self)
+	{
+		let mut in_use: MutexGuard<usize> = self.in_use.lock().unwrap();
+		*in_use -= 1;
+		self.released.notify_one();
+	}
+}
+
+/// A slot taken from a [`Pool`]; it is given back when dropped.
+#[derive(Debug)]
+pub struct PoolSlot<'pool>(&'pool Pool);
+
+impl Drop for PoolSlot<'_>
+{
+	fn drop(&mut self)
+	{
+		self.0.release();
+	}
+}
+
+/// Permission to run one rule: a job slot plus, if the rule names one, a slot in its pool.
+#[derive(Debug)]
+pub struct PoolToken<'pools>
+{
+	pool_slot: Option<PoolSlot<'pools>>,
+	job_slot: PoolSlot<'pools>,
+}
+
+/// The job pool sized by `--jobs` together with all named pools.
+#[derive(Debug)]
+pub struct PoolSet
+{
+	jobs: Pool,
+	pools: BTreeMap<String, Pool>,
+}
+
+impl PoolSet
+{
+	pub fn new(
+		jobs: usize,
+		depths: &BTreeMap<String, usize>,
+	) -> PoolSet
+	{
+		let jobs: Pool = Pool::new("jobs", jobs);
+		let mut pools: BTreeMap<String, Pool> = BTreeMap::new();
+		pools.insert(CONSOLE_POOL.to_string(), Pool::new(CONSOLE_POOL, 1));
+		for (name, depth) in depths
+		{
+			pools.insert(name.clone(), Pool::new(name, *depth));
+		}
+		PoolSet { jobs, pools }
+	}
+
+	pub fn jobs(&self) -> &Pool
+	{
+		&self.jobs
+	}
+
+	pub fn get(
+		&self,
+		name: &str,
+	) -> Result<&Pool, HepheastusError>
+	{
+		self.pools
+			.get(name)
+			.ok_or_else(|| HepheastusError::UnknownPool(name.to_string()))
+	}
+
+	pub fn pools(&self) -> impl Iterator<Item = &Pool>
+	{
+		self.pools.values()
+	}
+
+	/// Blocks until the named pool, if any, and the job pool both have a free slot.
+	///
+	/// The pool slot is taken first so that a rule waiting on a busy pool does not hold on to a job slot.
+	pub fn acquire(
+		&self,
+		pool_name: Option<&str>,
+	) -> Result<PoolToken<'_>, HepheastusError>
+	{
+		let pool_slot: Option<PoolSlot> = match pool_name
+		{
+			Option::Some(pool_name) => Option::Some(self.get(pool_name)?.acquire()),
+			Option::None => Option::None,
+		};
+		let job_slot: PoolSlot = self.jobs.acquire();
+		Result::Ok(PoolToken { pool_slot, job_slot })
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::collections::BTreeMap;
+
+	use crate::hilcode::build::pool::CONSOLE_POOL;
+	use crate::hilcode::build::pool::Pool;
+	use crate::hilcode::build::pool::PoolSet;
+	use crate::hilcode::build::pool::PoolSlot;
+	use crate::hilcode::build::pool::PoolToken;
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+	#[test]
+	fn try_acquire()
+	{
+		let pool: Pool = Pool::new("link", 2);
+		let first: Option<PoolSlot> = pool.try_acquire();
+		let second: Option<PoolSlot> = pool.try_acquire();
+		assert!(first.is_some());
+		assert!(second.is_some());
+		assert!(pool.try_acquire().is_none());
+		drop(first);
+		assert_eq!(1, pool.in_use());
+		assert!(pool.try_acquire().is_some());
+	}
+
+	#[test]
+	fn unlimited()
+	{
+		let pool: Pool = Pool::new("unlimited", 0);
+		let slots: Vec<PoolSlot> = (0..100).map(|_| pool.acquire()).collect();
+		assert_eq!(100, pool.in_use());
+		drop(slots);
+		assert_eq!(0, pool.in_use());
+	}
+
+	#[test]
+	fn acquire_from_pool_set()
+	{
+		let mut depths: BTreeMap<String, usize> = BTreeMap::new();
+		depths.insert("link".to_string(), 1);
+		let pool_set: PoolSet = PoolSet::new(4, &depths);
+		let token: PoolToken = pool_set.acquire(Option::Some("link")).unwrap();
+		assert_eq!(1, pool_set.get("link").unwrap().in_use());
+		assert_eq!(1, pool_set.jobs().in_use());
+		assert!(pool_set.get("link").unwrap().try_acquire().is_none());
+		drop(token);
+		assert_eq!(0, pool_set.get("link").unwrap().in_use());
+		assert_eq!(0, pool_set.jobs().in_use());
+	}
+
+	#[test]
+	fn console_pool()
+	{
+		let pool_set: PoolSet = PoolSet::new(4, &BTreeMap::new());
+		assert_eq!(1, pool_set.get(CONSOLE_POOL).unwrap().depth());
+	}
+
+	#[test]
+	fn unknown_pool()
+	{
+		let pool_set: PoolSet = PoolSet::new(4, &BTreeMap::new());
+		match pool_set.acquire(Option::Some("link"))
+		{
+			Result::Err(HepheastusError::UnknownPool(name)) => assert_eq!("link", name),
+			_ => panic!("expected an unknown pool error"),
+		}
+	}
+}
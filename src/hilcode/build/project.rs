@@ -0,0 +1,299 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::build::build_description::BUILD_FILE_NAME;
+use crate::hilcode::config::settings::CONFIG_FILE_NAME;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+/// The kind of project in a directory, as told by the files that mark it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProjectKind
+{
+	Cargo,
+	CMake,
+	Npm,
+	/// C sources without a build system hephaestus knows.
+	C,
+	Unknown,
+}
+
+impl ProjectKind
+{
+	/// The kind of project in `directory`; a `Cargo.toml` wins over a `CMakeLists.txt`, which wins over a
+	/// `package.json`, and C sources at the top or in `src` count only when there is none of these.
+	pub fn detect(directory: &Path) -> ProjectKind
+	{
+		if directory.join("Cargo.toml").is_file()
+		{
+			return ProjectKind::Cargo;
+		}
+		if directory.join("CMakeLists.txt").is_file()
+		{
+			return ProjectKind::CMake;
+		}
+		if directory.join("package.json").is_file()
+		{
+			return ProjectKind::Npm;
+		}
+		let has_c_sources = |directory: PathBuf| -> bool {
+			std::fs::read_dir(directory).is_ok_and(|entries: std::fs::ReadDir| {
+				entries.flatten().any(|entry: std::fs::DirEntry| {
+					entry
+						.path()
+						.extension()
+						.is_some_and(|extension| extension == "c" || extension == "h")
+				})
+			})
+		};
+		if has_c_sources(directory.to_path_buf()) || has_c_sources(directory.join("src"))
+		{
+			return ProjectKind::C;
+		}
+		ProjectKind::Unknown
+	}
+
+	/// A starter build description with file sets for this kind of project.
+	pub fn build_description(self) -> &'static str
+	{
+		match self
+		{
+			ProjectKind::Cargo => CARGO_BUILD_DESCRIPTION,
+			ProjectKind::CMake => CMAKE_BUILD_DESCRIPTION,
+			ProjectKind::Npm => NPM_BUILD_DESCRIPTION,
+			ProjectKind::C => C_BUILD_DESCRIPTION,
+			ProjectKind::Unknown => UNKNOWN_BUILD_DESCRIPTION,
+		}
+	}
+
+	/// A starter configuration, with a debug and a release profile that set what this kind of project varies between
+	/// the two.
+	pub fn config(self) -> String
+	{
+		let (name, debug, release): (&str, &str, &str) = match self
+		{
+			ProjectKind::Cargo => ("cargo_profile", "dev", "release"),
+			ProjectKind::CMake => ("build_type", "Debug", "Release"),
+			ProjectKind::Npm => ("node_env", "development", "production"),
+			ProjectKind::C | ProjectKind::Unknown => ("cflags", "-O0 -g", "-O2"),
+		};
+		format!(
+			"{}\n[profile debug]\nvariable.{} = {}\n\n[profile release]\nvariable.{} = {}\n",
+			CONFIG_HEADER, name, debug, name, release
+		)
+	}
+}
+
+impl Display for ProjectKind
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		let name: &str = match self
+		{
+			ProjectKind::Cargo => "Cargo",
+			ProjectKind::CMake => "CMake",
+			ProjectKind::Npm => "npm",
+			ProjectKind::C => "C",
+			ProjectKind::Unknown => "unknown",
+		};
+		formatter.write_str(name)
+	}
+}
+
+/// Writes a starter build description and configuration into `directory` for the kind of project it holds, and
+/// returns that kind. Existing files are only overwritten when `force` is set; otherwise nothing is written.
+pub fn init_project(
+	directory: &Path,
+	force: bool,
+) -> Result<ProjectKind, HepheastusError>
+{
+	let build_file: PathBuf = directory.join(BUILD_FILE_NAME);
+	let config_file: PathBuf = directory.join(CONFIG_FILE_NAME);
+	if !force
+		&& let Option::Some(existing) = [&build_file, &config_file]
+			.into_iter()
+			.find(|path: &&PathBuf| path.exists())
+	{
+		return Result::Err(HepheastusError::AlreadyExists(existing.clone()));
+	}
+	let project_kind: ProjectKind = ProjectKind::detect(directory);
+	std::fs::write(&build_file, project_kind.build_description())?;
+	std::fs::write(&config_file, project_kind.config())?;
+	Result::Ok(project_kind)
+}
+
+const CONFIG_HEADER: &str = "\
+# The configuration of this project. It overrides the system and user configuration files, and is overridden by
+# HEPHAESTUS_* environment variables and the command line; `hephaestus config show` shows where each setting comes
+# from.
+
+# jobs = 4
+# log_level = info
+# cache_policy = read-write
+";
+
+const CARGO_BUILD_DESCRIPTION: &str = "\
+# The build description of a Cargo project.
+
+[file_set sources]
+glob = src/**/*.rs
+glob = build.rs
+
+[file_set tests]
+glob = tests/**/*.rs
+glob = benches/**/*.rs
+glob = examples/**/*.rs
+
+[file_set manifests]
+glob = **/Cargo.toml
+glob = Cargo.lock
+glob = !target/**
+
+[file_set rust]
+set = @sources + @tests
+";
+
+const CMAKE_BUILD_DESCRIPTION: &str = "\
+# The build description of a CMake project. To use the rules CMake generates, configure with `cmake -G Ninja -B build`
+# and import them:
+#
+# [ninja cmake]
+# file = build/build.ninja
+
+[file_set sources]
+glob = **/*.c
+glob = **/*.cc
+glob = **/*.cpp
+glob = **/*.cxx
+glob = !build/**
+git_ignore = true
+
+[file_set headers]
+glob = **/*.h
+glob = **/*.hh
+glob = **/*.hpp
+glob = !build/**
+git_ignore = true
+
+[file_set cmake]
+glob = **/CMakeLists.txt
+glob = **/*.cmake
+glob = !build/**
+git_ignore = true
+";
+
+const NPM_BUILD_DESCRIPTION: &str = "\
+# The build description of an npm project.
+
+[file_set sources]
+glob = **/*.js
+glob = **/*.jsx
+glob = **/*.mjs
+glob = **/*.cjs
+glob = **/*.ts
+glob = **/*.tsx
+glob = !node_modules/**
+glob = !dist/**
+git_ignore = true
+
+[file_set manifests]
+glob = package.json
+glob = package-lock.json
+";
+
+const C_BUILD_DESCRIPTION: &str = "\
+# The build description of a C project.
+
+[file_set sources]
+glob = **/*.c
+git_ignore = true
+
+[file_set headers]
+glob = **/*.h
+git_ignore = true
+";
+
+const UNKNOWN_BUILD_DESCRIPTION: &str = "\
+# The build description of this project; define file sets, or import rules with a `[ninja name]` section.
+
+[file_set all]
+glob = **
+git_ignore = true
+";
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+
+	use crate::hilcode::build::build_description::BUILD_FILE_NAME;
+	use crate::hilcode::build::build_description::BuildDescription;
+	use crate::hilcode::build::project::ProjectKind;
+	use crate::hilcode::build::project::init_project;
+	use crate::hilcode::config::settings::CONFIG_FILE_NAME;
+	use crate::hilcode::config::settings::check_file;
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::parse::section_file::SectionFile;
+	use crate::hilcode::test::env::TestEnv;
+
+	#[test]
+	fn detect()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		assert_eq!(ProjectKind::Unknown, ProjectKind::detect(root_directory));
+		std::fs::create_dir(root_directory.join("src")).unwrap();
+		std::fs::write(root_directory.join("src/main.c"), "").unwrap();
+		assert_eq!(ProjectKind::C, ProjectKind::detect(root_directory));
+		std::fs::write(root_directory.join("package.json"), "{}").unwrap();
+		assert_eq!(ProjectKind::Npm, ProjectKind::detect(root_directory));
+		std::fs::write(root_directory.join("CMakeLists.txt"), "").unwrap();
+		assert_eq!(ProjectKind::CMake, ProjectKind::detect(root_directory));
+		std::fs::write(root_directory.join("Cargo.toml"), "").unwrap();
+		assert_eq!(ProjectKind::Cargo, ProjectKind::detect(root_directory));
+	}
+
+	#[test]
+	fn starters()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let config_file: &Path = &test_env.root_directory().join(CONFIG_FILE_NAME);
+		for project_kind in [
+			ProjectKind::Cargo,
+			ProjectKind::CMake,
+			ProjectKind::Npm,
+			ProjectKind::C,
+			ProjectKind::Unknown,
+		]
+		{
+			let section_file: SectionFile =
+				SectionFile::parse(Path::new(BUILD_FILE_NAME), project_kind.build_description()).unwrap();
+			assert!(BuildDescription::from_section_file(&section_file).is_ok(), "{}", project_kind);
+			std::fs::write(config_file, project_kind.config()).unwrap();
+			assert!(check_file(config_file).is_empty(), "{}", project_kind);
+		}
+	}
+
+	#[test]
+	fn init()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::write(root_directory.join("Cargo.toml"), "").unwrap();
+		assert_eq!(ProjectKind::Cargo, init_project(root_directory, false).unwrap());
+		let build_description: String = std::fs::read_to_string(root_directory.join(BUILD_FILE_NAME)).unwrap();
+		assert_eq!(ProjectKind::Cargo.build_description(), build_description);
+		std::fs::write(root_directory.join(BUILD_FILE_NAME), "# mine\n").unwrap();
+		assert!(matches!(init_project(root_directory, false), Result::Err(HepheastusError::AlreadyExists(_))));
+		assert_eq!("# mine\n", std::fs::read_to_string(root_directory.join(BUILD_FILE_NAME)).unwrap());
+		std::fs::remove_file(root_directory.join(BUILD_FILE_NAME)).unwrap();
+		assert!(matches!(init_project(root_directory, false), Result::Err(HepheastusError::AlreadyExists(_))));
+		assert_eq!(ProjectKind::Cargo, init_project(root_directory, true).unwrap());
+		assert!(root_directory.join(BUILD_FILE_NAME).is_file());
+	}
+}
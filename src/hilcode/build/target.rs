@@ -0,0 +1,167 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+/// One build step: running `rule` in `directory` turns the inputs into the outputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target
+{
+	directory: PathBuf,
+	rule: String,
+	outputs: Vec<PathBuf>,
+	implicit_outputs: Vec<PathBuf>,
+	inputs: Vec<PathBuf>,
+	implicit_inputs: Vec<PathBuf>,
+	order_only_inputs: Vec<PathBuf>,
+	command: Option<String>,
+	description: Option<String>,
+	depfile: Option<PathBuf>,
+	pool: Option<String>,
+}
+
+impl Target
+{
+	pub fn new(
+		directory: impl Into<PathBuf>,
+		rule: impl Into<String>,
+	) -> Target
+	{
+		let directory: PathBuf = directory.into();
+		let rule: String = rule.into();
+		Target {
+			directory,
+			rule,
+			outputs: Vec::new(),
+			implicit_outputs: Vec::new(),
+			inputs: Vec::new(),
+			implicit_inputs: Vec::new(),
+			order_only_inputs: Vec::new(),
+			command: Option::None,
+			description: Option::None,
+			depfile: Option::None,
+			pool: Option::None,
+		}
+	}
+
+	pub fn with_outputs(
+		self,
+		outputs: Vec<PathBuf>,
+		implicit_outputs: Vec<PathBuf>,
+	) -> Self
+	{
+		Self {
+			outputs,
+			implicit_outputs,
+			..self
+		}
+	}
+
+	pub fn with_inputs(
+		self,
+		inputs: Vec<PathBuf>,
+		implicit_inputs: Vec<PathBuf>,
+		order_only_inputs: Vec<PathBuf>,
+	) -> Self
+	{
+		Self {
+			inputs,
+			implicit_inputs,
+			order_only_inputs,
+			..self
+		}
+	}
+
+	pub fn with_command(
+		self,
+		command: Option<String>,
+	) -> Self
+	{
+		Self { command, ..self }
+	}
+
+	pub fn with_description(
+		self,
+		description: Option<String>,
+	) -> Self
+	{
+		Self { description, ..self }
+	}
+
+	pub fn with_depfile(
+		self,
+		depfile: Option<PathBuf>,
+	) -> Self
+	{
+		Self { depfile, ..self }
+	}
+
+	pub fn with_pool(
+		self,
+		pool: Option<String>,
+	) -> Self
+	{
+		Self { pool, ..self }
+	}
+
+	/// The directory the command runs in.
+	pub fn directory(&self) -> &Path
+	{
+		&self.directory
+	}
+
+	pub fn rule(&self) -> &str
+	{
+		&self.rule
+	}
+
+	pub fn outputs(&self) -> &[PathBuf]
+	{
+		&self.outputs
+	}
+
+	pub fn implicit_outputs(&self) -> &[PathBuf]
+	{
+		&self.implicit_outputs
+	}
+
+	/// All outputs, explicit ones first.
+	pub fn all_outputs(&self) -> impl Iterator<Item = &PathBuf>
+	{
+		self.outputs.iter().chain(self.implicit_outputs.iter())
+	}
+
+	pub fn inputs(&self) -> &[PathBuf]
+	{
+		&self.inputs
+	}
+
+	pub fn implicit_inputs(&self) -> &[PathBuf]
+	{
+		&self.implicit_inputs
+	}
+
+	pub fn order_only_inputs(&self) -> &[PathBuf]
+	{
+		&self.order_only_inputs
+	}
+
+	/// The command to run; `None` for phony targets.
+	pub fn command(&self) -> Option<&str>
+	{
+		self.command.as_deref()
+	}
+
+	pub fn description(&self) -> Option<&str>
+	{
+		self.description.as_deref()
+	}
+
+	pub fn depfile(&self) -> Option<&Path>
+	{
+		self.depfile.as_deref()
+	}
+
+	pub fn pool(&self) -> Option<&str>
+	{
+		self.pool.as_deref()
+	}
+}
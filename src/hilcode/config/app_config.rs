@@ -1,20 +1,47 @@
-use std::cmp::min;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::path::PathBuf;
-use std::rc::Rc;
+use std::sync::Arc;
 
+use time::UtcOffset;
+#[cfg(test)]
 use time::format_description::BorrowedFormatItem;
+use time::format_description::OwnedFormatItem;
+#[cfg(test)]
 use time::macros::format_description;
 
+use crate::hilcode::config::cache_policy::CachePolicy;
 use crate::hilcode::config::cli::Cli;
+use crate::hilcode::config::settings::DEFAULT_CACHE_DIRECTORY;
+#[cfg(test)]
+use crate::hilcode::config::settings::DEFAULT_PROFILE;
+use crate::hilcode::config::settings::DEFAULT_TIME_FORMAT;
+use crate::hilcode::config::settings::Setting;
+use crate::hilcode::config::settings::Settings;
+use crate::hilcode::config::settings::parse_time_format;
+use crate::hilcode::config::variables::Variables;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::io::normal_path;
 use crate::hilcode::log::level::LogLevel;
+use crate::hilcode::log::time_zone::TimeZone;
+
+/// The directory under the root directory where hephaestus keeps its own state.
+pub const STATE_DIRECTORY_NAME: &str = ".hephaestus";
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct AppConfig
 {
 	root_directory: PathBuf,
 	log_level: LogLevel,
-	time_format: &'static [BorrowedFormatItem<'static>],
+	time_format: OwnedFormatItem,
+	time_offset: Option<UtcOffset>,
+	jobs: usize,
+	pools: BTreeMap<String, usize>,
+	cache_directory: PathBuf,
+	cache_policy: CachePolicy,
+	profile: String,
+	variables: Variables,
+	settings: Settings,
 }
 
 impl AppConfig
@@ -24,14 +51,64 @@ impl AppConfig
 		&self.root_directory
 	}
 
+	pub fn state_directory(&self) -> PathBuf
+	{
+		self.root_directory.join(STATE_DIRECTORY_NAME)
+	}
+
 	pub fn log_level(&self) -> LogLevel
 	{
 		self.log_level
 	}
 
-	pub fn time_format(&self) -> &'static [BorrowedFormatItem<'static>]
+	pub fn time_format(&self) -> &OwnedFormatItem
+	{
+		&self.time_format
+	}
+
+	/// The offset of the time in front of log messages, or `None` to leave the time out.
+	pub fn time_offset(&self) -> Option<UtcOffset>
+	{
+		self.time_offset
+	}
+
+	pub fn jobs(&self) -> usize
+	{
+		self.jobs
+	}
+
+	pub fn pools(&self) -> &BTreeMap<String, usize>
+	{
+		&self.pools
+	}
+
+	/// Where hephaestus caches what it may need again; relative to the root directory unless absolute.
+	pub fn cache_directory(&self) -> &Path
+	{
+		&self.cache_directory
+	}
+
+	pub fn cache_policy(&self) -> CachePolicy
+	{
+		self.cache_policy
+	}
+
+	/// The name of the selected profile.
+	pub fn profile(&self) -> &str
+	{
+		&self.profile
+	}
+
+	/// The variables that configuration and build files refer to, built-in and user-defined.
+	pub fn variables(&self) -> &Variables
+	{
+		&self.variables
+	}
+
+	/// The settings behind this configuration as written, with where each came from.
+	pub fn settings(&self) -> &Settings
 	{
-		self.time_format
+		&self.settings
 	}
 }
 
@@ -49,61 +126,71 @@ impl Default for AppConfig
 {
 	fn default() -> Self
 	{
-		let root_directory: PathBuf = PathBuf::new().join(".");
-		AppConfig {
-			root_directory,
-			log_level: LogLevel::Info,
-			time_format: make_time_format(),
-		}
+		AppConfig::from_settings(Path::new("."), Settings::default()).expect("the defaults refer to no variables")
 	}
 }
 
 impl AppConfig
 {
+	/// A configuration from the defaults and `cli` alone, without reading any configuration file.
 	pub fn new(
 		root_directory: &Path,
 		cli: &Cli,
-	) -> Rc<Self>
+	) -> Arc<Self>
 	{
-		let root_directory: PathBuf = root_directory.to_path_buf();
-		let app_config: Self = Self {
-			root_directory,
-			log_level: make_log_level(cli),
-			time_format: make_time_format(),
-		};
-		Rc::new(app_config)
+		let mut settings: Settings = Settings::default();
+		settings.apply_cli(cli);
+		let app_config: AppConfig = AppConfig::from_settings(root_directory, settings)
+			.expect("the defaults and the command line refer to no variables");
+		Arc::new(app_config)
 	}
-}
 
-fn make_log_level(cli: &Cli) -> LogLevel
-{
-	let mut log_level: LogLevel = LogLevel::Info;
-	let mut quiet: u8 = cli.quiet;
-	let mut verbose: u8 = cli.verbose;
-	while quiet > 0 || verbose > 0
+	/// A configuration from the defaults, the system, user and project configuration files, and `cli`.
+	pub fn load(
+		root_directory: &Path,
+		cli: &Cli,
+	) -> Result<Arc<Self>, HepheastusError>
 	{
-		if quiet > 0 && verbose > 0
-		{
-			quiet -= min(cli.quiet, cli.verbose);
-			verbose -= min(cli.quiet, cli.verbose);
-		}
-		else if quiet > 0
-		{
-			quiet -= 1;
-			log_level = log_level.decrease();
-		}
-		else
-		{
-			verbose -= 1;
-			log_level = log_level.increase();
-		}
+		let settings: Settings = Settings::load(root_directory, cli)?;
+		Result::Ok(Arc::new(AppConfig::from_settings(root_directory, settings)?))
+	}
+
+	/// Takes the root directory as canonical, or as absolute and normalised if it does not exist, and the settings
+	/// with their variables resolved.
+	fn from_settings(
+		root_directory: &Path,
+		settings: Settings,
+	) -> Result<AppConfig, HepheastusError>
+	{
+		let root_directory: PathBuf = root_directory
+			.canonicalize()
+			.unwrap_or_else(|_| normal_path::absolute(root_directory));
+		let (resolved, variables) = settings.resolve(&root_directory)?;
+		let cache_directory: PathBuf = resolved
+			.parsed("cache_directory")
+			.unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_DIRECTORY));
+		Result::Ok(AppConfig {
+			cache_directory: root_directory.join(cache_directory),
+			root_directory,
+			log_level: resolved.parsed("log_level").unwrap_or(LogLevel::Info),
+			time_format: resolved
+				.get("time_format")
+				.and_then(|setting: &Setting| parse_time_format(setting.value()).ok())
+				.unwrap_or_else(make_time_format),
+			time_offset: resolved.parsed("time_zone").unwrap_or(TimeZone::Local).offset(),
+			jobs: resolved.parsed("jobs").unwrap_or(1),
+			pools: resolved.pools(),
+			cache_policy: resolved.parsed("cache_policy").unwrap_or(CachePolicy::ReadWrite),
+			profile: resolved.parsed("profile").unwrap_or_default(),
+			variables,
+			settings,
+		})
 	}
-	log_level
 }
 
-fn make_time_format() -> &'static [BorrowedFormatItem<'static>]
+fn make_time_format() -> OwnedFormatItem
 {
-	format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3] ")
+	parse_time_format(DEFAULT_TIME_FORMAT).unwrap()
 }
 
 #[cfg(test)]
@@ -111,7 +198,10 @@ pub struct AppConfigBuilder
 {
 	root_directory: PathBuf,
 	log_level: LogLevel,
-	time_format: &'static [BorrowedFormatItem<'static>],
+	time_format: OwnedFormatItem,
+	time_offset: Option<UtcOffset>,
+	jobs: usize,
+	pools: BTreeMap<String, usize>,
 }
 
 #[cfg(test)]
@@ -123,7 +213,10 @@ impl AppConfigBuilder
 		Self {
 			root_directory: PathBuf::new().join("."),
 			log_level: LogLevel::Trace,
-			time_format: format_description!(""),
+			time_format: OwnedFormatItem::from(format_description!("")),
+			time_offset: Option::Some(UtcOffset::UTC),
+			jobs: 1,
+			pools: BTreeMap::new(),
 		}
 	}
 
@@ -157,17 +250,58 @@ impl AppConfigBuilder
 		time_format: &'static [BorrowedFormatItem<'static>],
 	) -> Self
 	{
-		Self { time_format, ..self }
+		Self {
+			time_format: OwnedFormatItem::from(time_format),
+			..self
+		}
 	}
 
-	pub fn build(self) -> Rc<AppConfig>
+	pub fn with_time_zone(
+		self,
+		time_zone: TimeZone,
+	) -> Self
 	{
+		Self {
+			time_offset: time_zone.offset(),
+			..self
+		}
+	}
+
+	pub fn with_jobs(
+		self,
+		jobs: usize,
+	) -> Self
+	{
+		Self { jobs, ..self }
+	}
+
+	pub fn with_pool(
+		mut self,
+		name: &str,
+		depth: usize,
+	) -> Self
+	{
+		self.pools.insert(name.to_string(), depth);
+		self
+	}
+
+	pub fn build(self) -> Arc<AppConfig>
+	{
+		let variables: Variables = Variables::builtin(&self.root_directory, DEFAULT_PROFILE);
 		let app_config: AppConfig = AppConfig {
+			cache_directory: self.root_directory.join(DEFAULT_CACHE_DIRECTORY),
 			root_directory: self.root_directory,
 			log_level: self.log_level,
 			time_format: self.time_format,
+			time_offset: self.time_offset,
+			jobs: self.jobs,
+			pools: self.pools,
+			cache_policy: CachePolicy::ReadWrite,
+			profile: DEFAULT_PROFILE.to_string(),
+			variables,
+			settings: Settings::default(),
 		};
-		Rc::new(app_config)
+		Arc::new(app_config)
 	}
 }
 
@@ -178,14 +312,16 @@ mod tests
 
 	use std::path::Path;
 	use std::path::PathBuf;
-	use std::rc::Rc;
+	use std::sync::Arc;
 
 	use clap::Parser;
-	use time::format_description::BorrowedFormatItem;
+	use time::format_description::OwnedFormatItem;
 
 	use crate::hilcode::config::app_config::AppConfig;
 	use crate::hilcode::config::app_config::make_time_format;
 	use crate::hilcode::config::cli::Cli;
+	use crate::hilcode::config::settings::DEFAULT_TIME_FORMAT;
+	use crate::hilcode::config::settings::parse_time_format;
 	use crate::hilcode::log::level::LogLevel;
 	use crate::hilcode::test::env::TestEnv;
 
@@ -195,15 +331,15 @@ mod tests
 		let arguments: Vec<String> = to_arguments(vec!["/usr/bin/heph"]);
 		let cli: Cli = Cli::parse_from(&arguments);
 		let root_directory: PathBuf = PathBuf::new().join(".");
-		let app_config: Rc<AppConfig> = AppConfig::new(&root_directory, &cli);
-		assert_eq!(app_config, Rc::new(AppConfig::default()));
+		let app_config: Arc<AppConfig> = AppConfig::new(&root_directory, &cli);
+		assert_eq!(app_config, Arc::new(AppConfig::default()));
 	}
 
 	#[test]
 	fn log_level()
 	{
 		let log_level: LogLevel = LogLevel::Error;
-		let app_config: Rc<AppConfig> = AppConfig::builder().with_log_level(log_level).build();
+		let app_config: Arc<AppConfig> = AppConfig::builder().with_log_level(log_level).build();
 		let actual: LogLevel = app_config.log_level();
 		let expected: LogLevel = log_level;
 		assert_eq!(expected, actual);
@@ -213,9 +349,12 @@ mod tests
 	fn time_format()
 	{
 		let app_config: AppConfig = AppConfig::default();
-		let actual: &'static [BorrowedFormatItem<'static>] = app_config.time_format();
-		let expected: &'static [BorrowedFormatItem<'static>] = make_time_format();
-		assert_eq!(expected, actual);
+		let actual: &OwnedFormatItem = app_config.time_format();
+		let expected: OwnedFormatItem = make_time_format();
+		assert_eq!(&expected, actual);
+		assert_eq!(Result::Ok(expected), parse_time_format(DEFAULT_TIME_FORMAT));
+		assert_eq!(app_config.root_directory().join(".hephaestus/cache"), app_config.cache_directory());
+		assert!(app_config.root_directory().is_absolute());
 	}
 
 	#[test]
@@ -226,19 +365,19 @@ mod tests
 		{
 			let arguments: Vec<String> = to_arguments(vec!["/usr/bin/teng", "--verbose"]);
 			let cli: Cli = Cli::parse_from(arguments);
-			let app_config: Rc<AppConfig> = AppConfig::new(root_directory, &cli);
+			let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
 			assert_eq!(app_config.log_level, LogLevel::Debug);
 		}
 		{
 			let arguments: Vec<String> = to_arguments(vec!["/usr/bin/teng", "-v"]);
 			let cli: Cli = Cli::parse_from(arguments);
-			let app_config: Rc<AppConfig> = AppConfig::new(root_directory, &cli);
+			let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
 			assert_eq!(app_config.log_level, LogLevel::Debug);
 		}
 		{
 			let arguments: Vec<String> = to_arguments(vec!["/usr/bin/teng", "-vv"]);
 			let cli: Cli = Cli::parse_from(arguments);
-			let app_config: Rc<AppConfig> = AppConfig::new(root_directory, &cli);
+			let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
 			assert_eq!(app_config.log_level, LogLevel::Trace);
 		}
 	}
@@ -251,13 +390,13 @@ mod tests
 		{
 			let arguments: Vec<String> = to_arguments(vec!["/usr/bin/teng", "-vqq"]);
 			let cli: Cli = Cli::parse_from(arguments);
-			let app_config: Rc<AppConfig> = AppConfig::new(root_directory, &cli);
+			let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
 			assert_eq!(app_config.log_level, LogLevel::Warn);
 		}
 		{
 			let arguments: Vec<String> = to_arguments(vec!["/usr/bin/teng", "-vqv"]);
 			let cli: Cli = Cli::parse_from(arguments);
-			let app_config: Rc<AppConfig> = AppConfig::new(root_directory, &cli);
+			let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
 			assert_eq!(app_config.log_level, LogLevel::Debug);
 		}
 	}
@@ -270,23 +409,37 @@ mod tests
 		{
 			let arguments: Vec<String> = to_arguments(vec!["/usr/bin/teng", "--quiet"]);
 			let cli: Cli = Cli::parse_from(arguments);
-			let app_config: Rc<AppConfig> = AppConfig::new(root_directory, &cli);
+			let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
 			assert_eq!(app_config.log_level, LogLevel::Warn);
 		}
 		{
 			let arguments: Vec<String> = to_arguments(vec!["/usr/bin/teng", "-q"]);
 			let cli: Cli = Cli::parse_from(arguments);
-			let app_config: Rc<AppConfig> = AppConfig::new(root_directory, &cli);
+			let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
 			assert_eq!(app_config.log_level, LogLevel::Warn);
 		}
 		{
 			let arguments: Vec<String> = to_arguments(vec!["/usr/bin/teng", "-qq"]);
 			let cli: Cli = Cli::parse_from(arguments);
-			let app_config: Rc<AppConfig> = AppConfig::new(root_directory, &cli);
+			let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
 			assert_eq!(app_config.log_level, LogLevel::Error);
 		}
 	}
 
+	#[test]
+	fn with_jobs_and_pools()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		let arguments: Vec<String> =
+			to_arguments(vec!["/usr/bin/teng", "-j", "3", "--pool", "link=1", "--pool", "test=2"]);
+		let cli: Cli = Cli::parse_from(arguments);
+		let app_config: Arc<AppConfig> = AppConfig::new(root_directory, &cli);
+		assert_eq!(app_config.jobs(), 3);
+		assert_eq!(app_config.pools().get("link"), Some(&1));
+		assert_eq!(app_config.pools().get("test"), Some(&2));
+	}
+
 	fn to_arguments(arguments: Vec<&'static str>) -> Vec<String>
 	{
 		arguments.iter().map(|string| string.to_string()).collect()
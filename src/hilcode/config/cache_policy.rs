@@ -0,0 +1,63 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// How a build uses the cache directory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CachePolicy
+{
+	ReadWrite,
+	/// Use what is cached but add nothing, as on a CI machine that shares a cache it should not change.
+	ReadOnly,
+	Off,
+}
+
+impl FromStr for CachePolicy
+{
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err>
+	{
+		match text
+		{
+			"read-write" => Result::Ok(CachePolicy::ReadWrite),
+			"read-only" => Result::Ok(CachePolicy::ReadOnly),
+			"off" => Result::Ok(CachePolicy::Off),
+			_ => Result::Err(format!("expected read-write, read-only or off, not '{}'", text)),
+		}
+	}
+}
+
+impl Display for CachePolicy
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		let name: &str = match self
+		{
+			CachePolicy::ReadWrite => "read-write",
+			CachePolicy::ReadOnly => "read-only",
+			CachePolicy::Off => "off",
+		};
+		formatter.write_str(name)
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use crate::hilcode::config::cache_policy::CachePolicy;
+
+	#[test]
+	fn names()
+	{
+		for cache_policy in [CachePolicy::ReadWrite, CachePolicy::ReadOnly, CachePolicy::Off]
+		{
+			assert_eq!(Result::Ok(cache_policy), cache_policy.to_string().parse());
+		}
+		assert!("write-only".parse::<CachePolicy>().is_err());
+	}
+}
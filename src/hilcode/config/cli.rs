@@ -1,7 +1,17 @@
+use std::num::NonZero;
+use std::path::PathBuf;
+
 use clap::ArgAction;
 use clap::Parser;
+use clap::Subcommand;
+use clap_complete::ArgValueCandidates;
+use clap_complete::Shell;
 use const_format::concatcp;
 
+use crate::hilcode::config::completions::target_candidates;
+use crate::hilcode::config::settings::ConfigScope;
+use crate::hilcode::daemon::server::DEFAULT_IDLE_TIMEOUT;
+
 pub static BANNER: &str = {
 	static VERSION: &str = env!("CARGO_PKG_VERSION");
 	static DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
@@ -20,8 +30,21 @@ Copyright © 2025 Hilbrand C. Wijbenga. All rights reserved.
 	concatcp!(BANNER_PREFIX, VERSION, BANNER_SUFFIX, DESCRIPTION)
 };
 
+static ENVIRONMENT_HELP: &str = "\
+Environment variables:
+  HEPHAESTUS_LOG_LEVEL        Log level: off, error, warn, info, debug or trace
+  HEPHAESTUS_JOBS             Run at most this many rules at the same time
+  HEPHAESTUS_POOL_<NAME>      Depth of the pool <name>
+  HEPHAESTUS_TIME_FORMAT      Format of the time in front of log messages
+  HEPHAESTUS_TIME_ZONE        Time zone of that time: local, utc or none
+  HEPHAESTUS_CACHE_DIRECTORY  Where to cache, relative to the root directory unless absolute
+  HEPHAESTUS_CACHE_POLICY     How to use the cache: read-write, read-only or off
+  HEPHAESTUS_PROFILE          The profile to use, like --profile
+
+These override the configuration files, and command line options override them.";
+
 #[derive(Debug, Parser)]
-#[command(version, about, long_about(BANNER))]
+#[command(version, about, long_about(BANNER), after_help(ENVIRONMENT_HELP))]
 pub struct Cli
 {
 	/// Be more quiet
@@ -30,4 +53,133 @@ pub struct Cli
 	/// Be more verbose
 	#[arg(short, long, action = ArgAction::Count)]
 	pub verbose: u8,
+	/// Run at most this many rules at the same time
+	#[arg(short, long, value_parser = parse_jobs)]
+	pub jobs: Option<NonZero<usize>>,
+	/// Set the depth of a pool, overriding the build file (e.g. `--pool link=2`)
+	#[arg(long, value_name = "NAME=DEPTH", value_parser = parse_pool)]
+	pub pool: Vec<(String, usize)>,
+	/// Use the settings of this profile from the configuration files
+	#[arg(long, value_name = "NAME")]
+	pub profile: Option<String>,
+	/// Wait for another build in the same root directory to finish instead of failing
+	#[arg(long)]
+	pub wait: bool,
+	/// Use this root directory instead of looking for one from the current directory upwards
+	#[arg(long, value_name = "DIRECTORY")]
+	pub root: Option<PathBuf>,
+	/// Use the background daemon, starting it if needed, so file sets and file stats survive between builds
+	#[arg(long)]
+	pub daemon: bool,
+	/// What to build: outputs relative to the root directory, or `@name` for the files of a file set
+	#[arg(value_name = "TARGET", add = ArgValueCandidates::new(target_candidates))]
+	pub targets: Vec<String>,
+	#[command(subcommand)]
+	pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command
+{
+	/// Write a Clang compilation database for the C and C++ rules
+	CompileCommands
+	{
+		/// Where to write the database (default: `compile_commands.json` in the root directory)
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Print the script that completes command lines for a shell, e.g. `source <(hephaestus completions bash)`
+	Completions
+	{
+		#[arg(value_enum)]
+		shell: Shell,
+	},
+	/// Inspect and edit the configuration
+	Config
+	{
+		#[command(subcommand)]
+		command: ConfigCommand,
+	},
+	/// Run the daemon that keeps file sets and file stats in memory between builds
+	Daemon
+	{
+		/// Stop after this many seconds without requests
+		#[arg(long, value_name = "SECONDS", default_value_t = DEFAULT_IDLE_TIMEOUT.as_secs())]
+		idle_timeout: u64,
+		/// Stop the running daemon instead
+		#[arg(long)]
+		stop: bool,
+	},
+	/// Write a starter build description and configuration for the project in the current directory
+	Init
+	{
+		/// Overwrite an existing build description or configuration
+		#[arg(long)]
+		force: bool,
+	},
+	/// Print the manual page
+	Man,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand
+{
+	/// Show every effective setting and where its value came from
+	Show
+	{
+		/// Show values with the variables they refer to filled in, followed by the variables themselves
+		#[arg(long)]
+		resolved: bool,
+	},
+	/// Print the effective value of a setting
+	Get
+	{
+		key: String
+	},
+	/// Set a setting in a configuration file
+	Set
+	{
+		key: String,
+		#[arg(allow_hyphen_values = true)]
+		value: String,
+		/// The configuration file to change
+		#[arg(long, value_enum, default_value_t = ConfigScope::Project)]
+		scope: ConfigScope,
+	},
+	/// Remove a setting from a configuration file
+	Unset
+	{
+		key: String,
+		/// The configuration file to change
+		#[arg(long, value_enum, default_value_t = ConfigScope::Project)]
+		scope: ConfigScope,
+	},
+	/// List the settings in the configuration files, with the line that sets each
+	List
+	{
+		/// List only this configuration file
+		#[arg(long, value_enum)]
+		scope: Option<ConfigScope>,
+	},
+	/// Check the configuration files for unknown settings and invalid values
+	Validate,
+}
+
+/// Parses `--jobs` by the same rule as the `jobs` setting, so that `0` is refused here as it is in a configuration
+/// file.
+fn parse_jobs(text: &str) -> Result<NonZero<usize>, String>
+{
+	text.parse()
+		.map_err(|_| format!("jobs must be a positive number, not '{}'", text))
+}
+
+fn parse_pool(text: &str) -> Result<(String, usize), String>
+{
+	let (name, depth) = text
+		.split_once('=')
+		.ok_or_else(|| format!("expected NAME=DEPTH, not '{}'", text))?;
+	let depth: usize = depth
+		.parse()
+		.map_err(|_| format!("pool depth must be a non-negative number, not '{}'", depth))?;
+	Result::Ok((name.to_string(), depth))
 }
@@ -0,0 +1,167 @@
+use std::ffi::OsString;
+use std::io::Write;
+use std::sync::Arc;
+
+use clap::CommandFactory;
+use clap::Parser;
+use clap_complete::CompletionCandidate;
+use clap_complete::Shell;
+use clap_complete::env::EnvCompleter;
+use clap_complete::env::Shells;
+use clap_mangen::Man;
+use clap_mangen::roff::Roff;
+use clap_mangen::roff::roman;
+
+use crate::hilcode::build::build_description::BuildDescription;
+use crate::hilcode::config::app_config::AppConfig;
+use crate::hilcode::config::cli::BANNER;
+use crate::hilcode::config::cli::Cli;
+use crate::hilcode::config::root_directory::root_directory;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+/// The environment variable that asks hephaestus to complete a command line instead of running it.
+pub const COMPLETE_VARIABLE: &str = "COMPLETE";
+
+/// Writes the script that makes `shell` complete hephaestus command lines.
+///
+/// The script calls back into hephaestus for every completion, so that targets and file sets are completed from the
+/// build description as it is at that moment.
+pub fn write_completions(
+	shell: Shell,
+	writer: &mut dyn Write,
+) -> Result<(), HepheastusError>
+{
+	let name: &str = env!("CARGO_PKG_NAME");
+	let shells: Shells = Shells::builtins();
+	let completer: &dyn EnvCompleter = shells
+		.completer(&shell.to_string())
+		.ok_or_else(|| std::io::Error::other(format!("no completion support for {}", shell)))?;
+	completer.write_registration(COMPLETE_VARIABLE, name, name, name, writer)?;
+	Result::Ok(())
+}
+
+/// The targets and file sets of the build description in the root directory of the command line being completed; none
+/// when there is no build description or it cannot be loaded.
+pub fn target_candidates() -> Vec<CompletionCandidate>
+{
+	let cli: Cli = completed_cli(std::env::args_os());
+	let Result::Ok(root_directory) = root_directory(cli.root.as_deref())
+	else
+	{
+		return Vec::new();
+	};
+	let target_names: Result<Vec<String>, HepheastusError> =
+		AppConfig::load(&root_directory, &cli).and_then(|app_config: Arc<AppConfig>| {
+			BuildDescription::load(app_config.root_directory(), app_config.variables())
+				.map(|build_description: BuildDescription| build_description.target_names(app_config.root_directory()))
+		});
+	target_names
+		.unwrap_or_default()
+		.into_iter()
+		.map(|name: String| {
+			let help: &str = if name.starts_with('@') { "file set" } else { "target" };
+			CompletionCandidate::new(name).help(Option::Some(help.into()))
+		})
+		.collect()
+}
+
+/// The options of the command line being completed, so that `--root` and `--profile` are honoured.
+///
+/// While completing, the shell passes the command line after a `--`; its last word is the one being completed and is
+/// left out. When the rest does not parse, the defaults are used and the root is found from the current directory.
+fn completed_cli(args: impl IntoIterator<Item = OsString>) -> Cli
+{
+	let mut words: Vec<OsString> = args
+		.into_iter()
+		.skip_while(|arg: &OsString| arg != "--")
+		.skip(1)
+		.collect();
+	words.pop();
+	Cli::try_parse_from(words).unwrap_or_else(|_| Cli::parse_from([env!("CARGO_PKG_NAME")]))
+}
+
+/// Writes the manual page, which opens with the banner.
+pub fn write_man_page(writer: &mut dyn Write) -> Result<(), HepheastusError>
+{
+	let man: Man = Man::new(Cli::command());
+	man.render_title(writer)?;
+	man.render_name_section(writer)?;
+	man.render_synopsis_section(writer)?;
+	let mut description: Roff = Roff::new();
+	description.control("SH", ["DESCRIPTION"]).control("nf", []);
+	for line in BANNER.lines()
+	{
+		description.text([roman(line)]);
+	}
+	description.control("fi", []).to_writer(writer)?;
+	man.render_options_section(writer)?;
+	man.render_subcommands_section(writer)?;
+	man.render_extra_section(writer)?;
+	man.render_version_section(writer)?;
+	Result::Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::ffi::OsString;
+	use std::path::PathBuf;
+
+	use clap_complete::Shell;
+
+	use crate::hilcode::config::cli::Cli;
+	use crate::hilcode::config::completions::completed_cli;
+	use crate::hilcode::config::completions::write_completions;
+	use crate::hilcode::config::completions::write_man_page;
+
+	#[test]
+	fn completions()
+	{
+		for shell in [Shell::Bash, Shell::Elvish, Shell::Fish, Shell::PowerShell, Shell::Zsh]
+		{
+			let mut script: Vec<u8> = Vec::new();
+			write_completions(shell, &mut script).unwrap();
+			let script: String = String::from_utf8(script).unwrap();
+			assert!(script.contains("COMPLETE"), "{}: {}", shell, script);
+		}
+	}
+
+	#[test]
+	fn completed_options()
+	{
+		let args = |words: &[&str]| words.iter().map(OsString::from).collect::<Vec<OsString>>();
+		let cli: Cli = completed_cli(args(&[
+			"hephaestus",
+			"--",
+			"hephaestus",
+			"--root",
+			"/tmp",
+			"--profile",
+			"release",
+			"ta",
+		]));
+		assert_eq!(cli.root, Option::Some(PathBuf::from("/tmp")));
+		assert_eq!(cli.profile.as_deref(), Option::Some("release"));
+		assert!(cli.targets.is_empty());
+		let cli: Cli = completed_cli(args(&["hephaestus", "--", "hephaestus", "--root"]));
+		assert_eq!(cli.root, Option::None);
+		let cli: Cli = completed_cli(args(&["hephaestus", "--", "hephaestus", "--bogus", ""]));
+		assert_eq!(cli.root, Option::None);
+		let cli: Cli = completed_cli(args(&["hephaestus"]));
+		assert_eq!(cli.profile, Option::None);
+	}
+
+	#[test]
+	fn man_page()
+	{
+		let mut page: Vec<u8> = Vec::new();
+		write_man_page(&mut page).unwrap();
+		let page: String = String::from_utf8(page).unwrap();
+		assert!(page.starts_with(".ie"));
+		assert!(page.contains(".SH DESCRIPTION\n.nf\n"));
+		assert!(page.contains("Copyright"));
+		assert!(page.contains("HEPHAESTUS_JOBS"));
+	}
+}
@@ -0,0 +1,200 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::config::settings::setting_key;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::parse::section_file::Entry;
+use crate::hilcode::parse::section_file::Section;
+use crate::hilcode::parse::section_file::SectionFile;
+
+/// A configuration file as lines of text, edited in place so that its comments and layout survive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigFile
+{
+	path: PathBuf,
+	lines: Vec<String>,
+}
+
+impl ConfigFile
+{
+	/// The configuration file at `path`, which is empty if it does not exist yet.
+	pub fn load(path: &Path) -> Result<ConfigFile, HepheastusError>
+	{
+		let lines: Vec<String> = if path.exists()
+		{
+			std::fs::read_to_string(path)?.lines().map(str::to_string).collect()
+		}
+		else
+		{
+			Vec::new()
+		};
+		Result::Ok(ConfigFile {
+			path: path.to_path_buf(),
+			lines,
+		})
+	}
+
+	pub fn path(&self) -> &Path
+	{
+		&self.path
+	}
+
+	/// Sets `key` to `value`: in the entry that sets it now, if there is one, and otherwise in the last section that
+	/// `key` falls under, so `pool.link.depth` goes into `[pool link]` as `depth`, or else before the first section.
+	pub fn set(
+		&mut self,
+		key: &str,
+		value: &str,
+	) -> Result<(), HepheastusError>
+	{
+		let section_file: SectionFile = self.parse()?;
+		if let Option::Some(entry) = entries_of(&section_file, key).last()
+		{
+			let line: &mut String = &mut self.lines[entry.line() - 1];
+			let indentation: String = line.chars().take_while(|c: &char| c.is_whitespace()).collect();
+			*line = format!("{}{} = {}", indentation, entry.key(), value);
+			return Result::Ok(());
+		}
+		let section: Option<(&Section, &str)> = section_file
+			.sections()
+			.filter(|section: &&Section| !section.kind().is_empty())
+			.filter_map(|section: &Section| {
+				let prefix: String = match section.name()
+				{
+					Option::Some(name) => format!("{}.{}.", section.kind(), name),
+					Option::None => format!("{}.", section.kind()),
+				};
+				key.strip_prefix(&prefix)
+					.filter(|key: &&str| !key.is_empty())
+					.map(|key: &str| (section, key))
+			})
+			.last();
+		match section
+		{
+			Option::Some((section, entry_key)) =>
+			{
+				let index: usize = section.entries().last().map_or(section.line(), Entry::line);
+				self.lines.insert(index, format!("{} = {}", entry_key, value));
+			}
+			Option::None =>
+			{
+				let anonymous: Option<&Section> = section_file
+					.sections()
+					.find(|section: &&Section| section.kind().is_empty());
+				let first_header: Option<usize> = section_file
+					.sections()
+					.find(|section: &&Section| !section.kind().is_empty())
+					.map(Section::line);
+				let line: String = format!("{} = {}", key, value);
+				match (anonymous.and_then(|section: &Section| section.entries().last()), first_header)
+				{
+					(Option::Some(entry), _) => self.lines.insert(entry.line(), line),
+					(Option::None, Option::Some(header)) =>
+					{
+						self.lines.insert(header - 1, line);
+						self.lines.insert(header, String::new());
+					}
+					(Option::None, Option::None) => self.lines.push(line),
+				}
+			}
+		}
+		Result::Ok(())
+	}
+
+	/// Removes every entry that sets `key`, returning whether there was any.
+	pub fn unset(
+		&mut self,
+		key: &str,
+	) -> Result<bool, HepheastusError>
+	{
+		let section_file: SectionFile = self.parse()?;
+		let entries: Vec<&Entry> = entries_of(&section_file, key);
+		for entry in entries.iter().rev()
+		{
+			self.lines.remove(entry.line() - 1);
+		}
+		Result::Ok(!entries.is_empty())
+	}
+
+	/// Writes the file, creating the directory it goes in if needed.
+	pub fn save(&self) -> Result<(), HepheastusError>
+	{
+		if let Option::Some(directory) = self.path.parent()
+		{
+			std::fs::create_dir_all(directory)?;
+		}
+		let mut text: String = self.lines.join("\n");
+		text.push('\n');
+		std::fs::write(&self.path, text)?;
+		Result::Ok(())
+	}
+
+	fn parse(&self) -> Result<SectionFile, HepheastusError>
+	{
+		SectionFile::parse(&self.path, &self.lines.join("\n"))
+	}
+}
+
+/// The entries of `section_file` that set `key`, in order.
+fn entries_of<'a>(
+	section_file: &'a SectionFile,
+	key: &str,
+) -> Vec<&'a Entry>
+{
+	section_file
+		.sections()
+		.flat_map(|section: &Section| {
+			section
+				.entries()
+				.filter(move |entry: &&Entry| setting_key(section, entry) == key)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::PathBuf;
+
+	use crate::hilcode::config::config_file::ConfigFile;
+	use crate::hilcode::test::env::TestEnv;
+
+	#[test]
+	fn edits()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let path: PathBuf = test_env.root_directory().join("config/hephaestus.config");
+		let mut config_file: ConfigFile = ConfigFile::load(&path).unwrap();
+		config_file.set("jobs", "4").unwrap();
+		config_file.save().unwrap();
+		assert_eq!("jobs = 4\n", std::fs::read_to_string(&path).unwrap());
+		std::fs::write(&path, "# tuned by hand\n\n[pool link]\n  depth = 1\n\n[variable]\nout = out\n").unwrap();
+		let mut config_file: ConfigFile = ConfigFile::load(&path).unwrap();
+		config_file.set("pool.link.depth", "2").unwrap();
+		config_file.set("pool.test.depth", "3").unwrap();
+		config_file.set("variable.opt", "-O2").unwrap();
+		config_file.set("log_level", "debug").unwrap();
+		config_file.set("jobs", "8").unwrap();
+		config_file.save().unwrap();
+		assert_eq!(
+			concat!(
+				"# tuned by hand\n\n",
+				"pool.test.depth = 3\nlog_level = debug\njobs = 8\n\n",
+				"[pool link]\n  depth = 2\n\n",
+				"[variable]\nout = out\nopt = -O2\n",
+			),
+			std::fs::read_to_string(&path).unwrap()
+		);
+		let mut config_file: ConfigFile = ConfigFile::load(&path).unwrap();
+		assert!(config_file.unset("pool.link.depth").unwrap());
+		assert!(config_file.unset("jobs").unwrap());
+		assert!(!config_file.unset("time_zone").unwrap());
+		config_file.save().unwrap();
+		assert_eq!(
+			"# tuned by hand\n\npool.test.depth = 3\nlog_level = debug\n\n[pool link]\n\n[variable]\nout = out\nopt = -O2\n",
+			std::fs::read_to_string(&path).unwrap()
+		);
+	}
+}
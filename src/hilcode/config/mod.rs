@@ -1,3 +1,9 @@
 pub mod app_config;
+pub mod cache_policy;
 pub mod cli;
+pub mod completions;
+pub mod config_file;
 pub mod has_app_config;
+pub mod root_directory;
+pub mod settings;
+pub mod variables;
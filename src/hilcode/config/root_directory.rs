@@ -0,0 +1,77 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::build::build_description::BUILD_FILE_NAME;
+use crate::hilcode::config::app_config::STATE_DIRECTORY_NAME;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+/// The nearest directory at or above `directory` that holds a build description or a state directory.
+pub fn find_root_directory(directory: &Path) -> Option<PathBuf>
+{
+	directory
+		.ancestors()
+		.find(|directory: &&Path| {
+			directory.join(BUILD_FILE_NAME).is_file() || directory.join(STATE_DIRECTORY_NAME).is_dir()
+		})
+		.map(Path::to_path_buf)
+}
+
+/// The canonical root directory: `root` if given, otherwise the one found from the current directory, or the current
+/// directory itself when nothing marks a root above it, as in a project that is yet to be set up.
+pub fn root_directory(root: Option<&Path>) -> Result<PathBuf, HepheastusError>
+{
+	let root_directory: PathBuf = match root
+	{
+		Option::Some(root) => root.to_path_buf(),
+		Option::None =>
+		{
+			let current_directory: PathBuf = std::env::current_dir()?.canonicalize()?;
+			find_root_directory(&current_directory).unwrap_or(current_directory)
+		}
+	};
+	match root_directory.canonicalize()
+	{
+		Result::Ok(root_directory) if root_directory.is_dir() => Result::Ok(root_directory),
+		_ => Result::Err(HepheastusError::InvalidRootDirectory(root_directory)),
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::config::root_directory::find_root_directory;
+	use crate::hilcode::config::root_directory::root_directory;
+	use crate::hilcode::test::env::TestEnv;
+
+	#[test]
+	fn markers()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root: PathBuf = test_env.root_directory().canonicalize().unwrap();
+		std::fs::create_dir_all(root.join("project/src/deep")).unwrap();
+		std::fs::create_dir_all(root.join("other/.hephaestus")).unwrap();
+		std::fs::create_dir_all(root.join("other/sub")).unwrap();
+		std::fs::write(root.join("project/hephaestus.build"), "").unwrap();
+		assert_eq!(Option::Some(root.join("project")), find_root_directory(&root.join("project/src/deep")));
+		assert_eq!(Option::Some(root.join("project")), find_root_directory(&root.join("project")));
+		assert_eq!(Option::Some(root.join("other")), find_root_directory(&root.join("other/sub")));
+	}
+
+	#[test]
+	fn explicit_root()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root: &Path = test_env.root_directory();
+		std::fs::create_dir(root.join("sub")).unwrap();
+		std::fs::write(root.join("file"), "").unwrap();
+		assert_eq!(root.canonicalize().unwrap(), root_directory(Option::Some(&root.join("sub/.."))).unwrap());
+		assert!(root_directory(Option::Some(&root.join("file"))).is_err());
+		assert!(root_directory(Option::Some(&root.join("missing"))).is_err());
+		assert!(root_directory(Option::None).unwrap().is_absolute());
+	}
+}
@@ -0,0 +1,972 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::num::NonZero;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use const_format::concatcp;
+use time::format_description::OwnedFormatItem;
+
+use crate::hilcode::config::app_config::STATE_DIRECTORY_NAME;
+use crate::hilcode::config::cache_policy::CachePolicy;
+use crate::hilcode::config::cli::Cli;
+use crate::hilcode::config::variables::BUILTIN_VARIABLES;
+use crate::hilcode::config::variables::Value;
+use crate::hilcode::config::variables::Variables;
+use crate::hilcode::config::variables::has_references;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::log::level::LogLevel;
+use crate::hilcode::log::time_zone::TimeZone;
+use crate::hilcode::parse::section_file::Entry;
+use crate::hilcode::parse::section_file::Section;
+use crate::hilcode::parse::section_file::SectionFile;
+
+/// The name of a configuration file, in the root directory or in a system or user configuration directory.
+pub const CONFIG_FILE_NAME: &str = "hephaestus.config";
+
+/// The format of the time in front of log messages, as a `time` format description.
+pub const DEFAULT_TIME_FORMAT: &str = "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]";
+
+/// Where hephaestus caches what it may need again, relative to the root directory.
+pub const DEFAULT_CACHE_DIRECTORY: &str = concatcp!(STATE_DIRECTORY_NAME, "/cache");
+const SYSTEM_CONFIG_DIRECTORY: &str = "/etc/hephaestus";
+const USER_CONFIG_DIRECTORY_NAME: &str = "hephaestus";
+const ENVIRONMENT_PREFIX: &str = "HEPHAESTUS_";
+const POOL_ENVIRONMENT_PREFIX: &str = "POOL_";
+/// The settings that `HEPHAESTUS_` and the key in upper case override; pools have their own variables.
+const ENVIRONMENT_KEYS: [&str; 7] = [
+	"cache_directory",
+	"cache_policy",
+	"jobs",
+	"log_level",
+	"profile",
+	"time_format",
+	"time_zone",
+];
+/// The profile used when none is selected; it need not be defined.
+pub const DEFAULT_PROFILE: &str = "debug";
+const PROFILE_PREFIX: &str = "profile.";
+const VARIABLE_PREFIX: &str = "variable.";
+
+/// Where a configuration file lives; later scopes override earlier ones.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ConfigScope
+{
+	System,
+	User,
+	Project,
+}
+
+impl ConfigScope
+{
+	pub const ALL: [ConfigScope; 3] = [ConfigScope::System, ConfigScope::User, ConfigScope::Project];
+
+	/// The configuration file of this scope, or `None` for a user file when there is no home directory.
+	pub fn path(
+		self,
+		root_directory: &Path,
+	) -> Option<PathBuf>
+	{
+		match self
+		{
+			ConfigScope::System => Option::Some(Path::new(SYSTEM_CONFIG_DIRECTORY).join(CONFIG_FILE_NAME)),
+			ConfigScope::User =>
+			{
+				user_config_directory()
+					.map(|directory: PathBuf| directory.join(USER_CONFIG_DIRECTORY_NAME).join(CONFIG_FILE_NAME))
+			}
+			ConfigScope::Project => Option::Some(root_directory.join(CONFIG_FILE_NAME)),
+		}
+	}
+}
+
+/// `$XDG_CONFIG_HOME`, or `~/.config` when it is unset; as the XDG specification asks, a relative path is ignored.
+fn user_config_directory() -> Option<PathBuf>
+{
+	std::env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.filter(|directory: &PathBuf| directory.is_absolute())
+		.or_else(|| {
+			std::env::var_os("HOME")
+				.filter(|home| !home.is_empty())
+				.map(|home| PathBuf::from(home).join(".config"))
+		})
+}
+
+/// Where the effective value of a setting came from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigOrigin
+{
+	Default,
+	File
+	{
+		path: PathBuf,
+		line: usize,
+	},
+	/// The name of an environment variable.
+	Environment(String),
+	CommandLine,
+}
+
+impl Display for ConfigOrigin
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		match self
+		{
+			ConfigOrigin::Default => formatter.write_str("default"),
+			ConfigOrigin::File { path, line } => formatter.write_fmt(format_args!("{}:{}", path.display(), line)),
+			ConfigOrigin::Environment(name) => formatter.write_fmt(format_args!("${}", name)),
+			ConfigOrigin::CommandLine => formatter.write_str("command line"),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Setting
+{
+	value: String,
+	origin: ConfigOrigin,
+}
+
+impl Setting
+{
+	pub fn value(&self) -> &str
+	{
+		&self.value
+	}
+
+	pub fn origin(&self) -> &ConfigOrigin
+	{
+		&self.origin
+	}
+
+	/// An error about this setting of `key`, reported where its value came from.
+	fn error(
+		&self,
+		key: &str,
+		message: String,
+	) -> HepheastusError
+	{
+		match &self.origin
+		{
+			ConfigOrigin::File { path, line } => HepheastusError::parse_error(path, *line, message),
+			ConfigOrigin::Environment(name) =>
+			{
+				HepheastusError::InvalidEnvironmentVariable {
+					name: name.clone(),
+					value: self.value.clone(),
+					message,
+				}
+			}
+			ConfigOrigin::Default | ConfigOrigin::CommandLine =>
+			{
+				HepheastusError::InvalidSetting {
+					key: key.to_string(),
+					value: self.value.clone(),
+					message,
+				}
+			}
+		}
+	}
+}
+
+/// The effective settings by key, each remembering where its value came from.
+///
+/// Layers are applied from the built-in defaults through the system, user and project configuration files and the
+/// environment to the command line, each overriding what came before. A setting in a `[kind name]` section is known by
+/// its kind, name and key joined by dots, so `depth` in `[pool link]` is `pool.link.depth`. Values are checked as they
+/// are applied, so that a bad value is reported where it was written; a value that refers to variables, as in
+/// `${root}/out`, is checked when the settings are resolved.
+///
+/// A `[profile name]` section bundles settings under `profile.name.`, and may name a profile it `inherits` from. The
+/// selected profile overrides the configuration files, its own settings those of the profiles it inherits from, but
+/// not the environment or the command line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings
+{
+	settings: BTreeMap<String, Setting>,
+	/// The profiles with a section of their own, which defines them even when it holds no settings.
+	profiles: BTreeSet<String>,
+}
+
+impl Default for Settings
+{
+	fn default() -> Self
+	{
+		let mut settings: Settings = Settings {
+			settings: BTreeMap::new(),
+			profiles: BTreeSet::new(),
+		};
+		settings.insert("log_level", LogLevel::Info, ConfigOrigin::Default);
+		settings.insert("jobs", default_jobs(), ConfigOrigin::Default);
+		settings.insert("time_format", DEFAULT_TIME_FORMAT, ConfigOrigin::Default);
+		settings.insert("time_zone", TimeZone::Local, ConfigOrigin::Default);
+		settings.insert("cache_directory", DEFAULT_CACHE_DIRECTORY, ConfigOrigin::Default);
+		settings.insert("cache_policy", CachePolicy::ReadWrite, ConfigOrigin::Default);
+		settings.insert("profile", DEFAULT_PROFILE, ConfigOrigin::Default);
+		settings
+	}
+}
+
+impl Settings
+{
+	/// The defaults, overridden by the configuration files for `root_directory`, the selected profile, the environment
+	/// and then `cli`.
+	pub fn load(
+		root_directory: &Path,
+		cli: &Cli,
+	) -> Result<Settings, HepheastusError>
+	{
+		let mut settings: Settings = Settings::default();
+		for config_scope in ConfigScope::ALL
+		{
+			if let Option::Some(path) = config_scope.path(root_directory)
+			{
+				settings.apply_file(&path)?;
+			}
+		}
+		settings.apply_environment(std::env::vars_os())?;
+		settings.apply_profile(cli.profile.as_deref())?;
+		settings.apply_cli(cli);
+		Result::Ok(settings)
+	}
+
+	pub fn get(
+		&self,
+		key: &str,
+	) -> Option<&Setting>
+	{
+		self.settings.get(key)
+	}
+
+	/// The value of `key`, if it is set and valid.
+	pub fn parsed<T: FromStr>(
+		&self,
+		key: &str,
+	) -> Option<T>
+	{
+		self.settings
+			.get(key)
+			.and_then(|setting: &Setting| setting.value.parse().ok())
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &Setting)>
+	{
+		self.settings
+			.iter()
+			.map(|(key, setting): (&String, &Setting)| (key.as_str(), setting))
+	}
+
+	/// Sets `key` to `value` after checking that both make sense.
+	pub fn set(
+		&mut self,
+		key: &str,
+		value: &str,
+		origin: ConfigOrigin,
+	) -> Result<(), String>
+	{
+		validate(key, value)?;
+		self.insert(key, value, origin);
+		Result::Ok(())
+	}
+
+	/// Applies the configuration file at `path`, if there is one.
+	pub fn apply_file(
+		&mut self,
+		path: &Path,
+	) -> Result<(), HepheastusError>
+	{
+		if !path.is_file()
+		{
+			return Result::Ok(());
+		}
+		let section_file: SectionFile = SectionFile::load(path)?;
+		for section in section_file.sections()
+		{
+			if section.kind() == "profile"
+				&& let Option::Some(name) = section.name()
+			{
+				self.profiles.insert(name.to_string());
+			}
+			for entry in section.entries()
+			{
+				let origin: ConfigOrigin = ConfigOrigin::File {
+					path: path.to_path_buf(),
+					line: entry.line(),
+				};
+				self.set(&setting_key(section, entry), entry.value(), origin)
+					.map_err(|message: String| HepheastusError::parse_error(path, entry.line(), message))?;
+			}
+		}
+		Result::Ok(())
+	}
+
+	/// Applies the `HEPHAESTUS_*` variables among `variables`: `HEPHAESTUS_JOBS` sets `jobs`, and so on, while
+	/// `HEPHAESTUS_POOL_LINK` sets `pool.link.depth`. Variables hephaestus does not know are left alone.
+	pub fn apply_environment(
+		&mut self,
+		variables: impl IntoIterator<Item = (OsString, OsString)>,
+	) -> Result<(), HepheastusError>
+	{
+		for (name, value) in variables
+		{
+			let Option::Some(name) = name.to_str()
+			else
+			{
+				continue;
+			};
+			let Option::Some(key) = environment_key(name)
+			else
+			{
+				continue;
+			};
+			let invalid = |value: String, message: String| {
+				HepheastusError::InvalidEnvironmentVariable {
+					name: name.to_string(),
+					value,
+					message,
+				}
+			};
+			let value: String = value.into_string().map_err(|value: OsString| {
+				invalid(value.to_string_lossy().into_owned(), "not valid UTF-8".to_string())
+			})?;
+			self.set(&key, &value, ConfigOrigin::Environment(name.to_string()))
+				.map_err(|message: String| invalid(value.clone(), message))?;
+		}
+		Result::Ok(())
+	}
+
+	/// Applies the profile named `profile`, or else the one the settings so far select, with the profiles it inherits
+	/// from.
+	///
+	/// A profile is defined by a `[profile name]` section, even an empty one, or by any `profile.name.` setting. Naming
+	/// a profile that is not defined is an error, however it was named, unless it is the default profile.
+	pub fn apply_profile(
+		&mut self,
+		profile: Option<&str>,
+	) -> Result<(), HepheastusError>
+	{
+		if let Option::Some(profile) = profile
+		{
+			self.insert("profile", profile, ConfigOrigin::CommandLine);
+		}
+		let Option::Some(profile) = self.get("profile").cloned()
+		else
+		{
+			return Result::Ok(());
+		};
+		let mut chain: Vec<String> = Vec::new();
+		let mut next: Option<String> = Option::Some(profile.value.clone());
+		while let Option::Some(name) = next
+		{
+			if chain.contains(&name)
+			{
+				chain.push(name);
+				return Result::Err(HepheastusError::ProfileCycle(chain));
+			}
+			if !self.profiles.contains(&name) && !self.profile_settings(&name).any(|_| true)
+			{
+				if name == DEFAULT_PROFILE
+				{
+					break;
+				}
+				return Result::Err(HepheastusError::UnknownProfile(name));
+			}
+			next = self
+				.get(&format!("{}{}.inherits", PROFILE_PREFIX, name))
+				.map(|setting: &Setting| setting.value.clone());
+			chain.push(name);
+		}
+		for name in chain.iter().rev()
+		{
+			let overrides: Vec<(String, Setting)> = self
+				.profile_settings(name)
+				.filter(|(key, _): &(&str, &Setting)| *key != "inherits")
+				.map(|(key, setting): (&str, &Setting)| (key.to_string(), setting.clone()))
+				.collect();
+			for (key, setting) in overrides
+			{
+				let overridden: bool = self.get(&key).is_some_and(|current: &Setting| {
+					matches!(current.origin, ConfigOrigin::Environment(_) | ConfigOrigin::CommandLine)
+				});
+				if !overridden
+				{
+					self.settings.insert(key, setting);
+				}
+			}
+		}
+		Result::Ok(())
+	}
+
+	pub fn apply_cli(
+		&mut self,
+		cli: &Cli,
+	)
+	{
+		if cli.quiet > 0 || cli.verbose > 0
+		{
+			let log_level: LogLevel = self.parsed("log_level").unwrap_or(LogLevel::Info);
+			let log_level: LogLevel = adjust_log_level(log_level, cli.quiet, cli.verbose);
+			self.insert("log_level", log_level, ConfigOrigin::CommandLine);
+		}
+		if let Option::Some(profile) = &cli.profile
+		{
+			self.insert("profile", profile, ConfigOrigin::CommandLine);
+		}
+		if let Option::Some(jobs) = cli.jobs
+		{
+			self.insert("jobs", jobs, ConfigOrigin::CommandLine);
+		}
+		for (name, depth) in &cli.pool
+		{
+			self.insert(&format!("pool.{}.depth", name), depth, ConfigOrigin::CommandLine);
+		}
+	}
+
+	/// These settings with the variables they refer to filled in, and those variables.
+	///
+	/// Settings that refer to variables are only checked here, and an error is reported where the setting came from.
+	/// The settings of profiles are left as written, since only those of the selected profile matter and they were
+	/// applied already, and so are values from the command line, which are taken literally.
+	pub fn resolve(
+		&self,
+		root_directory: &Path,
+	) -> Result<(Settings, Variables), HepheastusError>
+	{
+		let profile: &str = self.get("profile").map(Setting::value).unwrap_or(DEFAULT_PROFILE);
+		let mut variables: Variables = Variables::builtin(root_directory, profile);
+		variables
+			.define_all(&self.variables())
+			.map_err(|(name, message): (String, String)| {
+				let key: String = format!("{}{}", VARIABLE_PREFIX, name);
+				self.settings[&key].error(&key, message)
+			})?;
+		let mut resolved: Settings = self.clone();
+		for (key, setting) in &self.settings
+		{
+			if key.starts_with(PROFILE_PREFIX)
+				|| setting.origin == ConfigOrigin::CommandLine
+				|| !has_references(&setting.value)
+			{
+				continue;
+			}
+			let value: String = match key.strip_prefix(VARIABLE_PREFIX)
+			{
+				Option::Some(name) => variables.lookup(name).map(|value: Value| value.to_string()),
+				Option::None => variables.interpolate(&setting.value),
+			}
+			.and_then(|value: String| validate(key, &value).map(|_| value))
+			.map_err(|message: String| setting.error(key, message))?;
+			resolved.insert(key, value, setting.origin.clone());
+		}
+		Result::Ok((resolved, variables))
+	}
+
+	/// The pool depths, from the `pool.NAME.depth` settings.
+	pub fn pools(&self) -> BTreeMap<String, usize>
+	{
+		self.settings
+			.iter()
+			.filter_map(|(key, setting): (&String, &Setting)| {
+				let name: &str = pool_name(key)?;
+				setting.value.parse().ok().map(|depth: usize| (name.to_string(), depth))
+			})
+			.collect()
+	}
+
+	/// The user-defined variables, from the `variable.NAME` settings.
+	pub fn variables(&self) -> BTreeMap<String, String>
+	{
+		self.settings
+			.iter()
+			.filter_map(|(key, setting): (&String, &Setting)| {
+				let name: &str = key.strip_prefix(VARIABLE_PREFIX)?;
+				Option::Some((name.to_string(), setting.value.clone()))
+			})
+			.collect()
+	}
+
+	/// The settings of the profile `name` by key, without the profile prefix.
+	fn profile_settings(
+		&self,
+		name: &str,
+	) -> impl Iterator<Item = (&str, &Setting)>
+	{
+		let prefix: String = format!("{}{}.", PROFILE_PREFIX, name);
+		let length: usize = prefix.len();
+		self.settings
+			.range(prefix.clone()..)
+			.take_while(move |(key, _): &(&String, &Setting)| key.starts_with(&prefix))
+			.map(move |(key, setting): (&String, &Setting)| (&key[length..], setting))
+	}
+
+	fn insert(
+		&mut self,
+		key: &str,
+		value: impl ToString,
+		origin: ConfigOrigin,
+	)
+	{
+		let value: String = value.to_string();
+		self.settings.insert(key.to_string(), Setting { value, origin });
+	}
+}
+
+/// The key of `entry` within `section`: its own key, prefixed with the section's kind and name if it has them.
+pub fn setting_key(
+	section: &Section,
+	entry: &Entry,
+) -> String
+{
+	match (section.kind(), section.name())
+	{
+		("", _) => entry.key().to_string(),
+		(kind, Option::None) => format!("{}.{}", kind, entry.key()),
+		(kind, Option::Some(name)) => format!("{}.{}.{}", kind, name, entry.key()),
+	}
+}
+
+/// Every problem with the configuration file at `path`, each reported at its line: keys that are not settings and
+/// values that do not suit their setting. A file that cannot be read or parsed yields that one error.
+pub fn check_file(path: &Path) -> Vec<HepheastusError>
+{
+	let section_file: SectionFile = match SectionFile::load(path)
+	{
+		Result::Ok(section_file) => section_file,
+		Result::Err(error) => return vec![error],
+	};
+	section_file
+		.sections()
+		.flat_map(|section: &Section| section.entries().map(move |entry: &Entry| (section, entry)))
+		.filter_map(|(section, entry): (&Section, &Entry)| {
+			validate(&setting_key(section, entry), entry.value())
+				.err()
+				.map(|message: String| HepheastusError::parse_error(path, entry.line(), message))
+		})
+		.collect()
+}
+
+/// The setting the environment variable `name` overrides, if any.
+fn environment_key(name: &str) -> Option<String>
+{
+	let name: &str = name.strip_prefix(ENVIRONMENT_PREFIX)?;
+	if let Option::Some(pool) = name.strip_prefix(POOL_ENVIRONMENT_PREFIX)
+	{
+		return (!pool.is_empty()).then(|| format!("pool.{}.depth", pool.to_ascii_lowercase()));
+	}
+	let key: String = name.to_ascii_lowercase();
+	ENVIRONMENT_KEYS.contains(&key.as_str()).then_some(key)
+}
+
+fn pool_name(key: &str) -> Option<&str>
+{
+	key.strip_prefix("pool.")?.strip_suffix(".depth")
+}
+
+/// What a setting holds, which decides the values it accepts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SettingType
+{
+	CacheDirectory,
+	CachePolicy,
+	Jobs,
+	LogLevel,
+	PoolDepth,
+	ProfileName,
+	TimeFormat,
+	TimeZone,
+	Variable,
+}
+
+impl SettingType
+{
+	/// The type of the setting `key`, or why there is no such setting.
+	pub fn of(key: &str) -> Result<SettingType, String>
+	{
+		match key
+		{
+			"cache_directory" => Result::Ok(SettingType::CacheDirectory),
+			"cache_policy" => Result::Ok(SettingType::CachePolicy),
+			"jobs" => Result::Ok(SettingType::Jobs),
+			"log_level" => Result::Ok(SettingType::LogLevel),
+			"profile" => Result::Ok(SettingType::ProfileName),
+			"time_format" => Result::Ok(SettingType::TimeFormat),
+			"time_zone" => Result::Ok(SettingType::TimeZone),
+			_ if pool_name(key).is_some_and(|name: &str| !name.is_empty()) => Result::Ok(SettingType::PoolDepth),
+			_ if key.starts_with(VARIABLE_PREFIX) =>
+			{
+				let name: &str = &key[VARIABLE_PREFIX.len()..];
+				if name.is_empty() || name.starts_with("env.") || BUILTIN_VARIABLES.contains(&name)
+				{
+					return Result::Err(format!("'{}' cannot be defined as a variable", name));
+				}
+				Result::Ok(SettingType::Variable)
+			}
+			_ =>
+			{
+				let Option::Some((name, key)) = key
+					.strip_prefix(PROFILE_PREFIX)
+					.and_then(|key: &str| key.split_once('.'))
+				else
+				{
+					return Result::Err(format!("unknown setting '{}'", key));
+				};
+				validate_profile_name(name)?;
+				match key
+				{
+					"inherits" => Result::Ok(SettingType::ProfileName),
+					"profile" => Result::Err(format!("profile '{}' cannot select another profile", name)),
+					_ if key.starts_with(PROFILE_PREFIX) =>
+					{
+						Result::Err(format!("profile '{}' cannot define profiles", name))
+					}
+					_ => SettingType::of(key),
+				}
+			}
+		}
+	}
+
+	pub fn check(
+		self,
+		value: &str,
+	) -> Result<(), String>
+	{
+		match self
+		{
+			SettingType::CacheDirectory if value.is_empty() =>
+			{
+				Result::Err("the cache directory cannot be empty".to_string())
+			}
+			SettingType::CacheDirectory | SettingType::Variable => Result::Ok(()),
+			SettingType::CachePolicy => value.parse::<CachePolicy>().map(|_| ()),
+			SettingType::Jobs =>
+			{
+				value
+					.parse::<NonZero<usize>>()
+					.map(|_| ())
+					.map_err(|_| format!("jobs must be a positive number, not '{}'", value))
+			}
+			SettingType::LogLevel => value.parse::<LogLevel>().map(|_| ()),
+			SettingType::PoolDepth =>
+			{
+				value
+					.parse::<usize>()
+					.map(|_| ())
+					.map_err(|_| format!("pool depth must be a non-negative number, not '{}'", value))
+			}
+			SettingType::ProfileName => validate_profile_name(value),
+			SettingType::TimeFormat =>
+			{
+				parse_time_format(value)
+					.map(|_| ())
+					.map_err(|error: String| format!("invalid time format '{}': {}", value, error))
+			}
+			SettingType::TimeZone => value.parse::<TimeZone>().map(|_| ()),
+		}
+	}
+}
+
+/// Checks that `key` is a setting and that `value` suits it; a value that refers to variables is checked once they
+/// are resolved.
+pub fn validate(
+	key: &str,
+	value: &str,
+) -> Result<(), String>
+{
+	let setting_type: SettingType = SettingType::of(key)?;
+	if has_references(value) && setting_type != SettingType::ProfileName
+	{
+		return Result::Ok(());
+	}
+	setting_type.check(value)
+}
+
+/// Profile names are plain words, so that they can be told apart in keys and never need resolving.
+fn validate_profile_name(name: &str) -> Result<(), String>
+{
+	if name.is_empty() || !name.chars().all(|c: char| c.is_alphanumeric() || c == '-' || c == '_')
+	{
+		return Result::Err(format!("'{}' is not a valid profile name", name));
+	}
+	Result::Ok(())
+}
+
+pub fn parse_time_format(text: &str) -> Result<OwnedFormatItem, String>
+{
+	time::format_description::parse_owned::<1>(text).map_err(|error| error.to_string())
+}
+
+/// `log_level` made `quiet` steps quieter and `verbose` steps more verbose, where a `-q` and a `-v` cancel out.
+fn adjust_log_level(
+	log_level: LogLevel,
+	quiet: u8,
+	verbose: u8,
+) -> LogLevel
+{
+	let mut log_level: LogLevel = log_level;
+	for _ in verbose.min(quiet)..quiet
+	{
+		log_level = log_level.decrease();
+	}
+	for _ in quiet.min(verbose)..verbose
+	{
+		log_level = log_level.increase();
+	}
+	log_level
+}
+
+fn default_jobs() -> usize
+{
+	std::thread::available_parallelism().map(NonZero::get).unwrap_or(1)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::collections::BTreeMap;
+	use std::ffi::OsString;
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use clap::Parser;
+
+	use crate::hilcode::config::cache_policy::CachePolicy;
+	use crate::hilcode::config::cli::Cli;
+	use crate::hilcode::config::settings::CONFIG_FILE_NAME;
+	use crate::hilcode::config::settings::ConfigOrigin;
+	use crate::hilcode::config::settings::ConfigScope;
+	use crate::hilcode::config::settings::DEFAULT_PROFILE;
+	use crate::hilcode::config::settings::Settings;
+	use crate::hilcode::config::settings::check_file;
+	use crate::hilcode::config::variables::Value;
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::log::level::LogLevel;
+	use crate::hilcode::test::env::TestEnv;
+
+	#[test]
+	fn layers()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		let user_file: PathBuf = root_directory.join("user.config");
+		std::fs::write(&user_file, "log_level = debug\njobs = 2\n\n[pool link]\ndepth = 1\n").unwrap();
+		let project_file: PathBuf = root_directory.join(CONFIG_FILE_NAME);
+		std::fs::write(&project_file, "# the project\njobs = 6\n").unwrap();
+		let mut settings: Settings = Settings::default();
+		settings.apply_file(&user_file).unwrap();
+		settings
+			.apply_file(&ConfigScope::Project.path(root_directory).unwrap())
+			.unwrap();
+		settings.apply_file(&root_directory.join("missing.config")).unwrap();
+		settings.apply_cli(&Cli::parse_from(["heph", "-q", "--pool", "test=3"]));
+		assert_eq!(Option::Some(LogLevel::Info), settings.parsed("log_level"));
+		assert_eq!(&ConfigOrigin::CommandLine, settings.get("log_level").unwrap().origin());
+		assert_eq!("6", settings.get("jobs").unwrap().value());
+		assert_eq!(
+			&ConfigOrigin::File {
+				path: project_file,
+				line: 2
+			},
+			settings.get("jobs").unwrap().origin()
+		);
+		assert_eq!(BTreeMap::from([("link".to_string(), 1), ("test".to_string(), 3)]), settings.pools());
+		assert_eq!(format!("{}:5", user_file.display()), settings.get("pool.link.depth").unwrap().origin().to_string());
+	}
+
+	#[test]
+	fn environment()
+	{
+		let mut settings: Settings = Settings::default();
+		let variables = |variables: &[(&str, &str)]| -> Vec<(OsString, OsString)> {
+			variables
+				.iter()
+				.map(|(name, value): &(&str, &str)| (OsString::from(name), OsString::from(value)))
+				.collect()
+		};
+		settings
+			.apply_environment(variables(&[
+				("HEPHAESTUS_JOBS", "5"),
+				("HEPHAESTUS_POOL_LINK", "1"),
+				("HEPHAESTUS_TIME_FORMAT", "[hour]:[minute]"),
+				("HEPHAESTUS_UNKNOWN", "ignored"),
+				("HEPHAESTUS_POOL_", "3"),
+				("JOBS", "7"),
+			]))
+			.unwrap();
+		assert_eq!(Option::Some(5), settings.parsed::<usize>("jobs"));
+		assert_eq!("$HEPHAESTUS_JOBS", settings.get("jobs").unwrap().origin().to_string());
+		assert_eq!(BTreeMap::from([("link".to_string(), 1)]), settings.pools());
+		assert_eq!("[hour]:[minute]", settings.get("time_format").unwrap().value());
+		settings.apply_cli(&Cli::parse_from(["heph", "--jobs", "2"]));
+		assert_eq!(Option::Some(2), settings.parsed::<usize>("jobs"));
+		assert!(Cli::try_parse_from(["heph", "--jobs", "0"]).is_err());
+		for (name, value) in [
+			("HEPHAESTUS_JOBS", "many"),
+			("HEPHAESTUS_LOG_LEVEL", "loud"),
+			("HEPHAESTUS_TIME_FORMAT", "[hour"),
+			("HEPHAESTUS_CACHE_DIRECTORY", ""),
+		]
+		{
+			let error: HepheastusError = Settings::default()
+				.apply_environment(variables(&[(name, value)]))
+				.unwrap_err();
+			assert!(
+				matches!(&error, HepheastusError::InvalidEnvironmentVariable { name: actual, .. } if actual == name),
+				"{}",
+				error
+			);
+		}
+	}
+
+	#[test]
+	fn profiles()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let path: PathBuf = test_env.root_directory().join(CONFIG_FILE_NAME);
+		std::fs::write(
+			&path,
+			concat!(
+				"jobs = 8\n",
+				"[profile debug]\nlog_level = debug\nvariable.opt = -O0\n",
+				"[profile release]\ninherits = debug\njobs = 4\nvariable.opt = -O2\ncache_policy = read-only\n",
+				"[profile ci]\ninherits = release\nlog_level = warn\n",
+				"[profile loop]\ninherits = loop\n",
+				"[profile empty]\n# Nothing yet.\n",
+			),
+		)
+		.unwrap();
+		let profile = |profile: Option<&str>, environment: &[(&str, &str)]| -> Result<Settings, HepheastusError> {
+			let mut settings: Settings = Settings::default();
+			settings.apply_file(&path)?;
+			settings.apply_environment(
+				environment
+					.iter()
+					.map(|(name, value): &(&str, &str)| (OsString::from(name), OsString::from(value))),
+			)?;
+			settings.apply_profile(profile)?;
+			Result::Ok(settings)
+		};
+		let settings: Settings = profile(Option::None, &[]).unwrap();
+		assert_eq!(Option::Some(LogLevel::Debug), settings.parsed("log_level"));
+		assert_eq!(Option::Some(8), settings.parsed::<usize>("jobs"));
+		let settings: Settings = profile(Option::Some("ci"), &[("HEPHAESTUS_JOBS", "2")]).unwrap();
+		assert_eq!(Option::Some(LogLevel::Warn), settings.parsed("log_level"));
+		assert_eq!(format!("{}:12", path.display()), settings.get("log_level").unwrap().origin().to_string());
+		assert_eq!(Option::Some(2), settings.parsed::<usize>("jobs"));
+		assert_eq!(Option::Some(CachePolicy::ReadOnly), settings.parsed("cache_policy"));
+		assert_eq!(BTreeMap::from([("opt".to_string(), "-O2".to_string())]), settings.variables());
+		let settings: Settings = profile(Option::None, &[("HEPHAESTUS_PROFILE", "release")]).unwrap();
+		assert_eq!(Option::Some(4), settings.parsed::<usize>("jobs"));
+		assert!(matches!(profile(Option::Some("missing"), &[]), Result::Err(HepheastusError::UnknownProfile(_))));
+		assert!(matches!(
+			profile(Option::None, &[("HEPHAESTUS_PROFILE", "missing")]),
+			Result::Err(HepheastusError::UnknownProfile(_))
+		));
+		assert_eq!(Option::Some(8), profile(Option::Some("empty"), &[]).unwrap().parsed::<usize>("jobs"));
+		assert!(matches!(profile(Option::Some("loop"), &[]), Result::Err(HepheastusError::ProfileCycle(_))));
+		assert_eq!(
+			Result::Ok(()),
+			Settings::default()
+				.apply_profile(Option::None)
+				.map_err(|error| error.to_string())
+		);
+		std::fs::write(&path, "profile = debug\njobs = 3\n[profile fast]\ninherits = debug\njobs = 16\n").unwrap();
+		assert_eq!(Option::Some(3), profile(Option::None, &[]).unwrap().parsed::<usize>("jobs"));
+		assert_eq!(
+			Option::Some(3),
+			profile(Option::Some(DEFAULT_PROFILE), &[])
+				.unwrap()
+				.parsed::<usize>("jobs")
+		);
+		assert_eq!(Option::Some(16), profile(Option::Some("fast"), &[]).unwrap().parsed::<usize>("jobs"));
+	}
+
+	#[test]
+	fn invalid_settings()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let path: PathBuf = test_env.root_directory().join(CONFIG_FILE_NAME);
+		for text in [
+			"jobs = 0\n",
+			"log_level = loud\n",
+			"colour = yes\n",
+			"[pool link]\ndepth = -1\n",
+		]
+		{
+			std::fs::write(&path, text).unwrap();
+			assert!(Settings::default().apply_file(&path).is_err(), "{}", text);
+		}
+		assert_eq!(Result::Ok(()), Settings::default().set("pool.link.depth", "4", ConfigOrigin::Default));
+		std::fs::write(&path, "jobs = 0\nlog_level = debug\n[pool link]\ndepth = ${depth}\ncolour = yes\n").unwrap();
+		let lines: Vec<usize> = check_file(&path)
+			.into_iter()
+			.map(|error: HepheastusError| {
+				match error
+				{
+					HepheastusError::ParseError { line, .. } => line,
+					_ => panic!("unexpected error: {}", error),
+				}
+			})
+			.collect();
+		assert_eq!(vec![1, 5], lines);
+	}
+
+	#[test]
+	fn variables()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		let path: PathBuf = root_directory.join(CONFIG_FILE_NAME);
+		std::fs::write(
+			&path,
+			concat!(
+				"cache_directory = ${out}/cache\n",
+				"jobs = ${jobs}\n",
+				"[variable]\nout = ${root}/out/${profile}\njobs = 3\nprice = $${5}\n",
+			),
+		)
+		.unwrap();
+		let mut settings: Settings = Settings::default();
+		settings.apply_file(&path).unwrap();
+		let (resolved, variables) = settings.resolve(root_directory).unwrap();
+		let out: String = format!("{}/out/debug", root_directory.display());
+		assert_eq!(format!("{}/cache", out), resolved.get("cache_directory").unwrap().value());
+		assert_eq!(Option::Some(3), resolved.parsed::<usize>("jobs"));
+		assert_eq!("${jobs}", settings.get("jobs").unwrap().value());
+		assert_eq!(Option::Some(&Value::Integer(3)), variables.get("jobs"));
+		assert_eq!(Option::Some(&Value::String("${5}".to_string())), variables.get("price"));
+		for (text, line) in [
+			("jobs = ${jobs}\n[variable]\njobs = none\n", 1),
+			("[variable]\nhome = ${env.HEPHAESTUS_SURELY_UNSET}\n", 2),
+			("log_level = ${missing}\n", 1),
+		]
+		{
+			std::fs::write(&path, text).unwrap();
+			let mut settings: Settings = Settings::default();
+			settings.apply_file(&path).unwrap();
+			let error: HepheastusError = settings.resolve(root_directory).unwrap_err();
+			assert!(matches!(error, HepheastusError::ParseError { line: actual, .. } if actual == line), "{}", error);
+		}
+		for text in ["[variable]\nroot = /\n", "profile = ${profile}\n"]
+		{
+			std::fs::write(&path, text).unwrap();
+			assert!(Settings::default().apply_file(&path).is_err(), "{}", text);
+		}
+		let mut settings: Settings = Settings::default();
+		settings
+			.apply_environment([(OsString::from("HEPHAESTUS_JOBS"), OsString::from("${none}"))])
+			.unwrap();
+		let error: HepheastusError = settings.resolve(root_directory).unwrap_err();
+		assert!(matches!(error, HepheastusError::InvalidEnvironmentVariable { .. }), "{}", error);
+	}
+}
@@ -0,0 +1,333 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::path::PathBuf;
+
+const ENVIRONMENT_PREFIX: &str = "env.";
+/// The variables hephaestus defines itself, which user-defined variables cannot replace.
+pub const BUILTIN_VARIABLES: [&str; 2] = ["profile", "root"];
+
+/// The value of a variable. User-defined variables take their type from how they are written: `true` and `false` are
+/// booleans, whole numbers are integers, and anything else is a string, with double quotes around it keeping
+/// `"true"` or `"8"` a string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value
+{
+	Boolean(bool),
+	Integer(i64),
+	Path(PathBuf),
+	String(String),
+}
+
+impl Value
+{
+	pub fn infer(text: &str) -> Value
+	{
+		if let Option::Some(text) = text.strip_prefix('"').and_then(|text: &str| text.strip_suffix('"'))
+		{
+			return Value::String(text.to_string());
+		}
+		match text
+		{
+			"true" => Value::Boolean(true),
+			"false" => Value::Boolean(false),
+			_ =>
+			{
+				text.parse::<i64>()
+					.map(Value::Integer)
+					.unwrap_or_else(|_| Value::String(text.to_string()))
+			}
+		}
+	}
+
+	pub fn type_name(&self) -> &'static str
+	{
+		match self
+		{
+			Value::Boolean(_) => "boolean",
+			Value::Integer(_) => "integer",
+			Value::Path(_) => "path",
+			Value::String(_) => "string",
+		}
+	}
+}
+
+impl Display for Value
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		match self
+		{
+			Value::Boolean(value) => value.fmt(formatter),
+			Value::Integer(value) => value.fmt(formatter),
+			Value::Path(path) => path.display().fmt(formatter),
+			Value::String(value) => formatter.write_str(value),
+		}
+	}
+}
+
+/// The variables that `${name}` in a configuration or build file refers to: the built-in `root` and `profile`, the
+/// user-defined `variable.NAME` settings, and `env.NAME` for the environment variable `NAME`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Variables
+{
+	values: BTreeMap<String, Value>,
+}
+
+impl Variables
+{
+	pub fn builtin(
+		root_directory: &Path,
+		profile: &str,
+	) -> Variables
+	{
+		let mut variables: Variables = Variables::default();
+		variables.define("profile", Value::String(profile.to_string()));
+		variables.define("root", Value::Path(root_directory.to_path_buf()));
+		variables
+	}
+
+	pub fn define(
+		&mut self,
+		name: &str,
+		value: Value,
+	)
+	{
+		self.values.insert(name.to_string(), value);
+	}
+
+	/// Defines a variable for each of `definitions`, in an order that resolves the references between them.
+	///
+	/// A definition that is nothing but a reference takes the type of what it refers to, so `${root}` stays a path;
+	/// any other definition is interpolated and then typed as it reads. On failure, the error names the variable whose
+	/// definition could not be resolved.
+	pub fn define_all(
+		&mut self,
+		definitions: &BTreeMap<String, String>,
+	) -> Result<(), (String, String)>
+	{
+		let mut pending: BTreeMap<&str, &str> = definitions
+			.iter()
+			.map(|(name, definition): (&String, &String)| (name.as_str(), definition.as_str()))
+			.collect();
+		while !pending.is_empty()
+		{
+			let ready: Vec<(&str, &str)> = pending
+				.iter()
+				.filter(|(_, definition): &(&&str, &&str)| {
+					references(definition)
+						.iter()
+						.all(|reference: &String| !pending.contains_key(reference.as_str()))
+				})
+				.map(|(name, definition): (&&str, &&str)| (*name, *definition))
+				.collect();
+			if ready.is_empty()
+			{
+				let names: Vec<&str> = pending.keys().copied().collect();
+				return Result::Err((
+					names[0].to_string(),
+					format!("variables refer to each other: {}", names.join(", ")),
+				));
+			}
+			for (name, definition) in ready
+			{
+				let value: Value = self
+					.evaluate(definition)
+					.map_err(|message: String| (name.to_string(), message))?;
+				self.define(name, value);
+				pending.remove(name);
+			}
+		}
+		Result::Ok(())
+	}
+
+	pub fn get(
+		&self,
+		name: &str,
+	) -> Option<&Value>
+	{
+		self.values.get(name)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)>
+	{
+		self.values
+			.iter()
+			.map(|(name, value): (&String, &Value)| (name.as_str(), value))
+	}
+
+	/// The value of the variable `name`, where `env.NAME` is the environment variable `NAME`.
+	pub fn lookup(
+		&self,
+		name: &str,
+	) -> Result<Value, String>
+	{
+		if let Option::Some(variable) = name.strip_prefix(ENVIRONMENT_PREFIX)
+		{
+			return std::env::var(variable)
+				.map(Value::String)
+				.map_err(|_| format!("environment variable '{}' is not set", variable));
+		}
+		self.values
+			.get(name)
+			.cloned()
+			.ok_or_else(|| format!("undefined variable '{}'", name))
+	}
+
+	/// `text` with every `${name}` replaced by the value of the variable `name`.
+	pub fn interpolate(
+		&self,
+		text: &str,
+	) -> Result<String, String>
+	{
+		interpolate(text, &mut |name: &str| self.lookup(name).map(|value: Value| value.to_string()))
+	}
+
+	fn evaluate(
+		&self,
+		definition: &str,
+	) -> Result<Value, String>
+	{
+		if let Option::Some(name) = definition
+			.strip_prefix("${")
+			.and_then(|name: &str| name.strip_suffix('}'))
+			.filter(|name: &&str| !name.contains('}'))
+		{
+			return self.lookup(name.trim());
+		}
+		self.interpolate(definition).map(|text: String| Value::infer(&text))
+	}
+}
+
+/// Whether `text` refers to any variable, so that it can only be checked once variables are resolved.
+pub fn has_references(text: &str) -> bool
+{
+	text.contains("${")
+}
+
+/// The names of the variables `text` refers to, in order.
+pub fn references(text: &str) -> Vec<String>
+{
+	let mut names: Vec<String> = Vec::new();
+	let _ = interpolate(text, &mut |name: &str| {
+		names.push(name.to_string());
+		Result::Ok(String::new())
+	});
+	names
+}
+
+/// Replaces every `${name}` in `text` with what `lookup` gives for `name`.
+///
+/// `$${` stands for a literal `${`, and a `$` that does not start a reference is kept as it is, so that regular
+/// expressions such as `\.rs$` need no escaping.
+fn interpolate(
+	text: &str,
+	lookup: &mut dyn FnMut(&str) -> Result<String, String>,
+) -> Result<String, String>
+{
+	let mut result: String = String::with_capacity(text.len());
+	let mut rest: &str = text;
+	while let Option::Some(index) = rest.find('$')
+	{
+		result.push_str(&rest[..index]);
+		rest = &rest[index..];
+		if let Option::Some(after) = rest.strip_prefix("$${")
+		{
+			result.push_str("${");
+			rest = after;
+		}
+		else if let Option::Some(reference) = rest.strip_prefix("${")
+		{
+			let end: usize = reference
+				.find('}')
+				.ok_or_else(|| format!("'${{' without a closing '}}' in '{}'", text))?;
+			let name: &str = reference[..end].trim();
+			if name.is_empty()
+			{
+				return Result::Err(format!("'${{}}' names no variable in '{}'", text));
+			}
+			result.push_str(&lookup(name)?);
+			rest = &reference[end + 1..];
+		}
+		else
+		{
+			result.push('$');
+			rest = &rest[1..];
+		}
+	}
+	result.push_str(rest);
+	Result::Ok(result)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::collections::BTreeMap;
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::config::variables::Value;
+	use crate::hilcode::config::variables::Variables;
+	use crate::hilcode::config::variables::references;
+
+	#[test]
+	fn values()
+	{
+		assert_eq!(Value::Boolean(true), Value::infer("true"));
+		assert_eq!(Value::Integer(-8), Value::infer("-8"));
+		assert_eq!(Value::String("8".to_string()), Value::infer("\"8\""));
+		assert_eq!(Value::String("-O2".to_string()), Value::infer("-O2"));
+		assert_eq!("/tmp", Value::Path(PathBuf::from("/tmp")).to_string());
+	}
+
+	#[test]
+	fn interpolation()
+	{
+		let variables: Variables = Variables::builtin(Path::new("/project"), "release");
+		assert_eq!(Result::Ok("/project/out/release".to_string()), variables.interpolate("${root}/out/${ profile }"));
+		assert_eq!(Result::Ok("${root} costs $5, ^a$".to_string()), variables.interpolate("$${root} costs $5, ^a$"));
+		assert_eq!(Result::Ok(std::env::var("PATH").unwrap()), variables.interpolate("${env.PATH}"));
+		assert!(variables.interpolate("${missing}").unwrap_err().contains("missing"));
+		assert!(variables.interpolate("${env.HEPHAESTUS_SURELY_UNSET}").is_err());
+		assert!(variables.interpolate("${root").is_err());
+		assert!(variables.interpolate("${}").is_err());
+		assert_eq!(vec!["a", "b"], references("${a}-$${c}-${b}"));
+	}
+
+	#[test]
+	fn definitions()
+	{
+		let definitions = |definitions: &[(&str, &str)]| -> BTreeMap<String, String> {
+			definitions
+				.iter()
+				.map(|(name, definition): &(&str, &str)| (name.to_string(), definition.to_string()))
+				.collect()
+		};
+		let mut variables: Variables = Variables::builtin(Path::new("/project"), "debug");
+		variables
+			.define_all(&definitions(&[
+				("a_out", "${out}/a"),
+				("jobs", "${count}"),
+				("count", "4"),
+				("out", "${root}"),
+			]))
+			.unwrap();
+		assert_eq!(Option::Some(&Value::Path(PathBuf::from("/project"))), variables.get("out"));
+		assert_eq!(Option::Some(&Value::String("/project/a".to_string())), variables.get("a_out"));
+		assert_eq!(Option::Some(&Value::Integer(4)), variables.get("jobs"));
+		let mut variables: Variables = Variables::default();
+		let error: (String, String) = variables
+			.define_all(&definitions(&[("a", "${b}"), ("b", "${a}"), ("c", "1")]))
+			.unwrap_err();
+		assert_eq!("a", error.0);
+		let error: (String, String) = Variables::default()
+			.define_all(&definitions(&[("a", "1"), ("b", "${a}${d}")]))
+			.unwrap_err();
+		assert_eq!(("b".to_string(), "undefined variable 'd'".to_string()), error);
+	}
+}
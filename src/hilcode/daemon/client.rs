@@ -0,0 +1,260 @@
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::hilcode::daemon::protocol::Request;
+use crate::hilcode::daemon::protocol::Response;
+use crate::hilcode::daemon::protocol::SOCKET_FILE_NAME;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::io::normal_path;
+
+const START_TIMEOUT: Duration = Duration::from_secs(5);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(20);
+
+pub struct DaemonClient
+{
+	reader: BufReader<UnixStream>,
+	writer: UnixStream,
+}
+
+impl DaemonClient
+{
+	pub fn connect(state_directory: &Path) -> Result<DaemonClient, HepheastusError>
+	{
+		let stream: UnixStream = UnixStream::connect(state_directory.join(SOCKET_FILE_NAME))?;
+		let writer: UnixStream = stream.try_clone()?;
+		let reader: BufReader<UnixStream> = BufReader::new(stream);
+		Result::Ok(DaemonClient { reader, writer })
+	}
+
+	/// Connects to the daemon for `root_directory`, starting one in the background if none is running.
+	pub fn connect_or_start(
+		root_directory: &Path,
+		state_directory: &Path,
+		idle_timeout: Duration,
+	) -> Result<DaemonClient, HepheastusError>
+	{
+		if let Result::Ok(daemon_client) = DaemonClient::connect(state_directory)
+		{
+			return Result::Ok(daemon_client);
+		}
+		std::process::Command::new(std::env::current_exe()?)
+			.arg("daemon")
+			.arg("--idle-timeout")
+			.arg(idle_timeout.as_secs().to_string())
+			.current_dir(root_directory)
+			.stdin(Stdio::null())
+			.stdout(Stdio::null())
+			.stderr(Stdio::null())
+			.spawn()?;
+		let started: Instant = Instant::now();
+		loop
+		{
+			match DaemonClient::connect(state_directory)
+			{
+				Result::Ok(daemon_client) => return Result::Ok(daemon_client),
+				Result::Err(_) if started.elapsed() < START_TIMEOUT => std::thread::sleep(RETRY_INTERVAL),
+				Result::Err(error) => return Result::Err(error),
+			}
+		}
+	}
+
+	pub fn ping(&mut self) -> Result<(), HepheastusError>
+	{
+		match self.request(&Request::Ping)?
+		{
+			Response::Pong => Result::Ok(()),
+			response => Result::Err(unexpected(response)),
+		}
+	}
+
+	pub fn search(
+		&mut self,
+		name: &str,
+		base_directory: &Path,
+		globs: &[String],
+	) -> Result<Vec<PathBuf>, HepheastusError>
+	{
+		let request: Request = Request::Search {
+			name: name.to_string(),
+			base_directory: normal_path::absolute(base_directory),
+			globs: globs.to_vec(),
+		};
+		match self.request(&request)?
+		{
+			Response::Files(files) => Result::Ok(files),
+			response => Result::Err(unexpected(response)),
+		}
+	}
+
+	/// The file stat of `path`, rendered the way [`FileStat`](crate::hilcode::io::file_stat::FileStat) displays.
+	pub fn stat(
+		&mut self,
+		path: &Path,
+	) -> Result<String, HepheastusError>
+	{
+		match self.request(&Request::Stat(path.to_path_buf()))?
+		{
+			Response::Stat(file_stat) => Result::Ok(file_stat),
+			response => Result::Err(unexpected(response)),
+		}
+	}
+
+	pub fn shutdown(&mut self) -> Result<(), HepheastusError>
+	{
+		match self.request(&Request::Shutdown)?
+		{
+			Response::Done => Result::Ok(()),
+			response => Result::Err(unexpected(response)),
+		}
+	}
+
+	fn request(
+		&mut self,
+		request: &Request,
+	) -> Result<Response, HepheastusError>
+	{
+		self.writer.write_all(request.encode().as_bytes())?;
+		self.writer.flush()?;
+		let mut line: String = String::new();
+		if self.reader.read_line(&mut line)? == 0
+		{
+			return Result::Err(HepheastusError::ProtocolError("the daemon hung up".to_string()));
+		}
+		Response::decode(&line)
+	}
+}
+
+fn unexpected(response: Response) -> HepheastusError
+{
+	match response
+	{
+		Response::Error(message) => HepheastusError::DaemonError(message),
+		response => HepheastusError::ProtocolError(format!("unexpected response {:?}", response)),
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::io::BufRead;
+	use std::io::BufReader;
+	use std::io::Lines;
+	use std::io::Write;
+	use std::os::unix::net::UnixStream;
+	use std::path::Path;
+	use std::path::PathBuf;
+	use std::thread::JoinHandle;
+	use std::time::Duration;
+	use std::time::SystemTime;
+
+	use crate::hilcode::daemon::client::DaemonClient;
+	use crate::hilcode::daemon::protocol::Response;
+	use crate::hilcode::daemon::server::DaemonServer;
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::test::env::TestEnv;
+
+	#[test]
+	fn search_stat_and_shutdown()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: PathBuf = test_env.root_directory().to_path_buf();
+		let state_directory: PathBuf = root_directory.join(".hephaestus");
+		std::fs::write(root_directory.join("main.rs"), "fn main() {}\n").unwrap();
+		let daemon_server: DaemonServer = DaemonServer::new(&state_directory, Duration::from_secs(10));
+		let socket_path: PathBuf = daemon_server.socket_path().to_path_buf();
+		let server: JoinHandle<Result<(), HepheastusError>> = std::thread::spawn(move || daemon_server.run());
+		while !socket_path.exists()
+		{
+			std::thread::sleep(Duration::from_millis(10));
+		}
+		let mut daemon_client: DaemonClient = DaemonClient::connect(&state_directory).unwrap();
+		daemon_client.ping().unwrap();
+		let globs: Vec<String> = vec!["**/*.rs".to_string(), "!.hephaestus/**".to_string()];
+		let files: Vec<PathBuf> = daemon_client.search("sources", &root_directory, &globs).unwrap();
+		assert_eq!(vec![root_directory.join("main.rs")], files);
+		std::fs::write(root_directory.join("lib.rs"), "\n").unwrap();
+		let files: Vec<PathBuf> = daemon_client.search("sources", &root_directory, &globs).unwrap();
+		assert_eq!(vec![root_directory.join("lib.rs"), root_directory.join("main.rs")], files);
+		let file_stat: String = daemon_client.stat(&root_directory.join("main.rs")).unwrap();
+		assert!(file_stat.contains("|13B|"));
+		let main_rs: std::fs::File = std::fs::File::options()
+			.write(true)
+			.open(root_directory.join("main.rs"))
+			.unwrap();
+		let modified: SystemTime = main_rs.metadata().unwrap().modified().unwrap();
+		(&main_rs).write_all(b"fn nain() {}\n").unwrap();
+		main_rs.set_modified(modified).unwrap();
+		assert_ne!(file_stat, daemon_client.stat(&root_directory.join("main.rs")).unwrap());
+		assert!(daemon_client.stat(Path::new("/does/not/exist")).is_err());
+		daemon_client.shutdown().unwrap();
+		server.join().unwrap().unwrap();
+		assert!(!socket_path.exists());
+	}
+
+	#[test]
+	fn concurrent_clients()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let state_directory: PathBuf = test_env.root_directory().join(".hephaestus");
+		let daemon_server: DaemonServer = DaemonServer::new(&state_directory, Duration::from_millis(500));
+		let socket_path: PathBuf = daemon_server.socket_path().to_path_buf();
+		let server: JoinHandle<Result<(), HepheastusError>> = std::thread::spawn(move || daemon_server.run());
+		while !socket_path.exists()
+		{
+			std::thread::sleep(Duration::from_millis(10));
+		}
+		let mut first: DaemonClient = DaemonClient::connect(&state_directory).unwrap();
+		first.ping().unwrap();
+		let mut second: DaemonClient = DaemonClient::connect(&state_directory).unwrap();
+		second.ping().unwrap();
+		drop(second);
+		first.ping().unwrap();
+		server.join().unwrap().unwrap();
+		assert!(!socket_path.exists());
+	}
+
+	#[test]
+	fn connection_errors_are_reported()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let state_directory: PathBuf = test_env.root_directory().join(".hephaestus");
+		let daemon_server: DaemonServer = DaemonServer::new(&state_directory, Duration::from_millis(500));
+		let socket_path: PathBuf = daemon_server.socket_path().to_path_buf();
+		let server: JoinHandle<Result<(), HepheastusError>> = std::thread::spawn(move || daemon_server.run());
+		while !socket_path.exists()
+		{
+			std::thread::sleep(Duration::from_millis(10));
+		}
+		let mut stream: UnixStream = UnixStream::connect(&socket_path).unwrap();
+		stream.write_all(b"PING\nSTAT\t\xff\n").unwrap();
+		let mut lines: Lines<BufReader<UnixStream>> = BufReader::new(stream).lines();
+		assert_eq!(Response::Pong, Response::decode(&lines.next().unwrap().unwrap()).unwrap());
+		match Response::decode(&lines.next().unwrap().unwrap()).unwrap()
+		{
+			Response::Error(message) => assert!(message.contains("UTF-8"), "{}", message),
+			response => panic!("expected an error, not {:?}", response),
+		}
+		assert!(lines.next().is_none());
+		server.join().unwrap().unwrap();
+	}
+
+	#[test]
+	fn idle_timeout()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let state_directory: PathBuf = test_env.root_directory().join(".hephaestus");
+		let daemon_server: DaemonServer = DaemonServer::new(&state_directory, Duration::from_millis(100));
+		daemon_server.run().unwrap();
+		assert!(!daemon_server.socket_path().exists());
+	}
+}
@@ -0,0 +1,265 @@
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::ffi::OsStringExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+pub const SOCKET_FILE_NAME: &str = "daemon.sock";
+
+/// What a client asks the daemon; every request and response is one line of tab-separated, escaped fields.
+///
+/// Paths travel as the bytes the operating system uses for them, so a path that is not valid UTF-8 arrives intact.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Request
+{
+	Ping,
+	Search
+	{
+		name: String,
+		base_directory: PathBuf,
+		globs: Vec<String>,
+	},
+	Stat(PathBuf),
+	Shutdown,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Response
+{
+	Pong,
+	Files(Vec<PathBuf>),
+	Stat(String),
+	Done,
+	Error(String),
+}
+
+impl Request
+{
+	pub fn encode(&self) -> String
+	{
+		match self
+		{
+			Request::Ping => encode_fields(&[b"PING"]),
+			Request::Search {
+				name,
+				base_directory,
+				globs,
+			} =>
+			{
+				let mut fields: Vec<&[u8]> = vec![b"SEARCH", name.as_bytes(), path_bytes(base_directory)];
+				fields.extend(globs.iter().map(String::as_bytes));
+				encode_fields(&fields)
+			}
+			Request::Stat(path) => encode_fields(&[b"STAT", path_bytes(path)]),
+			Request::Shutdown => encode_fields(&[b"SHUTDOWN"]),
+		}
+	}
+
+	pub fn decode(line: &str) -> Result<Request, HepheastusError>
+	{
+		let fields: Vec<Vec<u8>> = decode_fields(line)?;
+		match fields.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>().as_slice()
+		{
+			[b"PING"] => Result::Ok(Request::Ping),
+			[b"SEARCH", name, base_directory, globs @ ..] =>
+			{
+				Result::Ok(Request::Search {
+					name: text(name)?,
+					base_directory: path(base_directory),
+					globs: globs.iter().map(|glob: &&[u8]| text(glob)).collect::<Result<_, _>>()?,
+				})
+			}
+			[b"STAT", stat_path] => Result::Ok(Request::Stat(path(stat_path))),
+			[b"SHUTDOWN"] => Result::Ok(Request::Shutdown),
+			_ => Result::Err(HepheastusError::ProtocolError(format!("unknown request '{}'", line))),
+		}
+	}
+}
+
+impl Response
+{
+	pub fn encode(&self) -> String
+	{
+		match self
+		{
+			Response::Pong => encode_fields(&[b"PONG"]),
+			Response::Files(files) =>
+			{
+				let mut fields: Vec<&[u8]> = vec![b"FILES"];
+				fields.extend(files.iter().map(|file: &PathBuf| path_bytes(file)));
+				encode_fields(&fields)
+			}
+			Response::Stat(file_stat) => encode_fields(&[b"STAT", file_stat.as_bytes()]),
+			Response::Done => encode_fields(&[b"DONE"]),
+			Response::Error(message) => encode_fields(&[b"ERROR", message.as_bytes()]),
+		}
+	}
+
+	pub fn decode(line: &str) -> Result<Response, HepheastusError>
+	{
+		let fields: Vec<Vec<u8>> = decode_fields(line)?;
+		match fields.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>().as_slice()
+		{
+			[b"PONG"] => Result::Ok(Response::Pong),
+			[b"FILES", files @ ..] =>
+			{
+				Result::Ok(Response::Files(files.iter().map(|file: &&[u8]| path(file)).collect()))
+			}
+			[b"STAT", file_stat] => Result::Ok(Response::Stat(text(file_stat)?)),
+			[b"DONE"] => Result::Ok(Response::Done),
+			[b"ERROR", message] => Result::Ok(Response::Error(text(message)?)),
+			_ => Result::Err(HepheastusError::ProtocolError(format!("unknown response '{}'", line))),
+		}
+	}
+}
+
+fn path_bytes(path: &Path) -> &[u8]
+{
+	path.as_os_str().as_bytes()
+}
+
+fn path(field: &[u8]) -> PathBuf
+{
+	PathBuf::from(OsString::from_vec(field.to_vec()))
+}
+
+fn text(field: &[u8]) -> Result<String, HepheastusError>
+{
+	String::from_utf8(field.to_vec())
+		.map_err(|_| HepheastusError::ProtocolError(format!("invalid UTF-8 in '{}'", String::from_utf8_lossy(field))))
+}
+
+fn encode_fields(fields: &[&[u8]]) -> String
+{
+	let fields: Vec<String> = fields.iter().map(|field: &&[u8]| escape(field)).collect();
+	let mut line: String = fields.join("\t");
+	line.push('\n');
+	line
+}
+
+fn decode_fields(line: &str) -> Result<Vec<Vec<u8>>, HepheastusError>
+{
+	line.trim_end_matches('\n').split('\t').map(unescape).collect()
+}
+
+/// Escapes the separators in `field`, and writes the bytes that are not UTF-8 as `\xHH`.
+fn escape(field: &[u8]) -> String
+{
+	let mut text: String = String::with_capacity(field.len());
+	for chunk in field.utf8_chunks()
+	{
+		for character in chunk.valid().chars()
+		{
+			match character
+			{
+				'\\' => text.push_str("\\\\"),
+				'\t' => text.push_str("\\t"),
+				'\n' => text.push_str("\\n"),
+				character => text.push(character),
+			}
+		}
+		for byte in chunk.invalid()
+		{
+			text.push_str(&format!("\\x{:02x}", byte));
+		}
+	}
+	text
+}
+
+fn unescape(field: &str) -> Result<Vec<u8>, HepheastusError>
+{
+	let bad_escape = || -> HepheastusError { HepheastusError::ProtocolError(format!("bad escape in '{}'", field)) };
+	let mut bytes: Vec<u8> = Vec::with_capacity(field.len());
+	let mut characters = field.chars();
+	while let Option::Some(character) = characters.next()
+	{
+		if character != '\\'
+		{
+			bytes.extend_from_slice(character.encode_utf8(&mut [0; 4]).as_bytes());
+			continue;
+		}
+		match characters.next()
+		{
+			Option::Some('\\') => bytes.push(b'\\'),
+			Option::Some('t') => bytes.push(b'\t'),
+			Option::Some('n') => bytes.push(b'\n'),
+			Option::Some('x') =>
+			{
+				let hex: String = characters.by_ref().take(2).collect();
+				if hex.len() != 2
+				{
+					return Result::Err(bad_escape());
+				}
+				bytes.push(u8::from_str_radix(&hex, 16).map_err(|_| bad_escape())?);
+			}
+			_ => return Result::Err(bad_escape()),
+		}
+	}
+	Result::Ok(bytes)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::ffi::OsStr;
+	use std::os::unix::ffi::OsStrExt;
+	use std::path::PathBuf;
+
+	use crate::hilcode::daemon::protocol::Request;
+	use crate::hilcode::daemon::protocol::Response;
+
+	#[test]
+	fn request_round_trip()
+	{
+		let requests: Vec<Request> = vec![
+			Request::Ping,
+			Request::Search {
+				name: "rust-sources".to_string(),
+				base_directory: PathBuf::from("my\tproject"),
+				globs: vec!["**/*.rs".to_string(), "!target/**".to_string()],
+			},
+			Request::Stat(PathBuf::from("src\\main.rs")),
+			Request::Stat(PathBuf::from(OsStr::from_bytes(b"caf\xe9\\x41.rs"))),
+			Request::Shutdown,
+		];
+		for request in requests
+		{
+			let line: String = request.encode();
+			assert_eq!(1, line.lines().count());
+			assert_eq!(request, Request::decode(&line).unwrap());
+		}
+	}
+
+	#[test]
+	fn response_round_trip()
+	{
+		let responses: Vec<Response> = vec![
+			Response::Pong,
+			Response::Files(vec![
+				PathBuf::from("a.rs"),
+				PathBuf::from("b\nc.rs"),
+				PathBuf::from(OsStr::from_bytes(b"\xff")),
+			]),
+			Response::Files(Vec::new()),
+			Response::Stat("2025-01-01T00:00:00.000|12B|rw-r--r--|0".to_string()),
+			Response::Done,
+			Response::Error("no such file".to_string()),
+		];
+		for response in responses
+		{
+			assert_eq!(response, Response::decode(&response.encode()).unwrap());
+		}
+	}
+
+	#[test]
+	fn unknown_request()
+	{
+		assert!(Request::decode("BUILD everything\n").is_err());
+		assert!(Request::decode("STAT\tbad\\x4\n").is_err());
+		assert!(Request::decode("SEARCH\t\\xff\t/\n").is_err());
+	}
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::os::unix::net::UnixListener;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use crate::hilcode::daemon::protocol::Request;
+use crate::hilcode::daemon::protocol::Response;
+use crate::hilcode::daemon::protocol::SOCKET_FILE_NAME;
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::io::file_set::FileSet;
+use crate::hilcode::io::file_set_glob::FileSetGlob;
+use crate::hilcode::io::file_stat::FileStat;
+
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// File systems record modification times with a coarse clock; a directory or file changed this close to a search or
+/// hash may change again without its modification time moving, so it never vouches for a cached result.
+const TIMESTAMP_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// A file set found earlier, valid for as long as none of the directories it was searched in has changed.
+struct CachedFileSet
+{
+	file_set: FileSet,
+	searched: SystemTime,
+	directories: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl CachedFileSet
+{
+	fn is_current(&self) -> bool
+	{
+		let trusted_before: SystemTime = self.searched - TIMESTAMP_GRANULARITY;
+		self.directories.iter().all(|(directory, modified)| {
+			modified.is_some_and(|modified: SystemTime| modified < trusted_before)
+				&& modification_time(directory) == *modified
+		})
+	}
+}
+
+/// A file stat taken earlier, valid for as long as the file has not changed since.
+struct CachedFileStat
+{
+	file_stat: FileStat,
+	hashed: SystemTime,
+}
+
+impl CachedFileStat
+{
+	fn is_current(
+		&self,
+		metadata: &Metadata,
+	) -> bool
+	{
+		let trusted_before: SystemTime = self.hashed - TIMESTAMP_GRANULARITY;
+		metadata
+			.modified()
+			.is_ok_and(|modified: SystemTime| modified < trusted_before)
+			&& self.file_stat.is_current(metadata)
+	}
+}
+
+/// Keeps file sets and file stats in memory and serves them over a Unix domain socket in the state directory.
+///
+/// Every client is served on a thread of its own, so one build holding a connection open does not keep others
+/// waiting. The server stops when told to or after `idle_timeout` without requests from any client.
+pub struct DaemonServer
+{
+	socket_path: PathBuf,
+	idle_timeout: Duration,
+	state: Arc<DaemonState>,
+}
+
+/// What the threads serving clients share.
+struct DaemonState
+{
+	file_sets: Mutex<HashMap<(PathBuf, Vec<String>), CachedFileSet>>,
+	file_stats: Mutex<HashMap<PathBuf, CachedFileStat>>,
+	last_request: Mutex<Instant>,
+	/// The requests being answered, which keep the daemon from stopping for being idle.
+	requests: AtomicUsize,
+	shutting_down: AtomicBool,
+}
+
+impl DaemonServer
+{
+	pub fn new(
+		state_directory: &Path,
+		idle_timeout: Duration,
+	) -> DaemonServer
+	{
+		DaemonServer {
+			socket_path: state_directory.join(SOCKET_FILE_NAME),
+			idle_timeout,
+			state: Arc::new(DaemonState {
+				file_sets: Mutex::new(HashMap::new()),
+				file_stats: Mutex::new(HashMap::new()),
+				last_request: Mutex::new(Instant::now()),
+				requests: AtomicUsize::new(0),
+				shutting_down: AtomicBool::new(false),
+			}),
+		}
+	}
+
+	pub fn socket_path(&self) -> &Path
+	{
+		&self.socket_path
+	}
+
+	pub fn run(&self) -> Result<(), HepheastusError>
+	{
+		let listener: UnixListener = self.bind()?;
+		listener.set_nonblocking(true)?;
+		self.state.touch();
+		let result: Result<(), HepheastusError> = loop
+		{
+			match listener.accept()
+			{
+				Result::Ok((stream, _)) =>
+				{
+					self.state.touch();
+					let state: Arc<DaemonState> = Arc::clone(&self.state);
+					std::thread::spawn(move || {
+						if let Result::Err(error) = state.serve(&stream)
+						{
+							report_error(&stream, &error);
+						}
+					});
+				}
+				Result::Err(error) if error.kind() == ErrorKind::WouldBlock =>
+				{
+					if self.state.shutting_down.load(Ordering::SeqCst) || self.state.is_idle(self.idle_timeout)
+					{
+						break Result::Ok(());
+					}
+					std::thread::sleep(POLL_INTERVAL);
+				}
+				Result::Err(error) => break Result::Err(HepheastusError::IoError(error)),
+			}
+		};
+		let _ = std::fs::remove_file(&self.socket_path);
+		result
+	}
+
+	/// Binds the socket, replacing a socket file left behind by a daemon that did not shut down cleanly.
+	fn bind(&self) -> Result<UnixListener, HepheastusError>
+	{
+		if let Option::Some(parent) = self.socket_path.parent()
+		{
+			std::fs::create_dir_all(parent)?;
+		}
+		if self.socket_path.exists()
+		{
+			if UnixStream::connect(&self.socket_path).is_ok()
+			{
+				return Result::Err(HepheastusError::DaemonAlreadyRunning(self.socket_path.clone()));
+			}
+			std::fs::remove_file(&self.socket_path)?;
+		}
+		Result::Ok(UnixListener::bind(&self.socket_path)?)
+	}
+}
+
+impl DaemonState
+{
+	fn touch(&self)
+	{
+		*self.last_request.lock().unwrap() = Instant::now();
+	}
+
+	fn is_idle(
+		&self,
+		idle_timeout: Duration,
+	) -> bool
+	{
+		self.requests.load(Ordering::SeqCst) == 0 && self.last_request.lock().unwrap().elapsed() >= idle_timeout
+	}
+
+	/// Answers requests on `stream` until the client hangs up or asks the daemon to shut down.
+	fn serve(
+		&self,
+		stream: &UnixStream,
+	) -> Result<(), HepheastusError>
+	{
+		stream.set_nonblocking(false)?;
+		let mut writer: &UnixStream = stream;
+		let reader: BufReader<&UnixStream> = BufReader::new(stream);
+		for line in reader.lines()
+		{
+			let line: String = line?;
+			self.requests.fetch_add(1, Ordering::SeqCst);
+			let request: Result<Request, HepheastusError> = Request::decode(&line);
+			let shutdown: bool = matches!(request, Result::Ok(Request::Shutdown));
+			let response: Response = match request
+			{
+				Result::Ok(request) => self.handle(request),
+				Result::Err(error) => Response::Error(error.to_string()),
+			};
+			self.touch();
+			self.requests.fetch_sub(1, Ordering::SeqCst);
+			writer.write_all(response.encode().as_bytes())?;
+			writer.flush()?;
+			if shutdown
+			{
+				self.shutting_down.store(true, Ordering::SeqCst);
+				break;
+			}
+		}
+		Result::Ok(())
+	}
+
+	fn handle(
+		&self,
+		request: Request,
+	) -> Response
+	{
+		let response: Result<Response, HepheastusError> = match request
+		{
+			Request::Ping => Result::Ok(Response::Pong),
+			Request::Search {
+				name,
+				base_directory,
+				globs,
+			} => self.search(name, base_directory, globs).map(Response::Files),
+			Request::Stat(path) =>
+			{
+				self.stat(path)
+					.map(|file_stat: FileStat| Response::Stat(file_stat.to_string()))
+			}
+			Request::Shutdown => Result::Ok(Response::Done),
+		};
+		response.unwrap_or_else(|error: HepheastusError| Response::Error(error.to_string()))
+	}
+
+	fn search(
+		&self,
+		name: String,
+		base_directory: PathBuf,
+		globs: Vec<String>,
+	) -> Result<Vec<PathBuf>, HepheastusError>
+	{
+		let key: (PathBuf, Vec<String>) = (base_directory, globs);
+		if let Option::Some(cached) = self.file_sets.lock().unwrap().get(&key)
+			&& cached.is_current()
+		{
+			return Result::Ok(cached.file_set.absolute_paths().collect());
+		}
+		let searched: SystemTime = SystemTime::now();
+		let file_set_glob: FileSetGlob = FileSetGlob::new(&key.0, key.1.clone());
+		let directories: Vec<(PathBuf, Option<SystemTime>)> = file_set_glob
+			.directories()?
+			.into_iter()
+			.map(|directory: PathBuf| {
+				let modified: Option<SystemTime> = modification_time(&directory);
+				(directory, modified)
+			})
+			.collect();
+		let file_set: FileSet = file_set_glob.search(name)?;
+		let files: Vec<PathBuf> = file_set.absolute_paths().collect();
+		self.file_sets.lock().unwrap().insert(
+			key,
+			CachedFileSet {
+				file_set,
+				searched,
+				directories,
+			},
+		);
+		Result::Ok(files)
+	}
+
+	fn stat(
+		&self,
+		path: PathBuf,
+	) -> Result<FileStat, HepheastusError>
+	{
+		let metadata: Metadata = path.metadata()?;
+		if let Option::Some(cached) = self.file_stats.lock().unwrap().get(&path)
+			&& cached.is_current(&metadata)
+		{
+			return Result::Ok(cached.file_stat.clone());
+		}
+		let hashed: SystemTime = SystemTime::now();
+		let file_stat: FileStat = FileStat::get(&path)?;
+		self.file_stats.lock().unwrap().insert(
+			path,
+			CachedFileStat {
+				file_stat: file_stat.clone(),
+				hashed,
+			},
+		);
+		Result::Ok(file_stat)
+	}
+}
+
+/// Reports an error that ends a connection: to the client, if the connection still allows it, and on standard error for
+/// a daemon that was started in the foreground.
+fn report_error(
+	mut stream: &UnixStream,
+	error: &HepheastusError,
+)
+{
+	eprintln!("{}", error);
+	let _ = stream.write_all(Response::Error(error.to_string()).encode().as_bytes());
+}
+
+fn modification_time(path: &Path) -> Option<SystemTime>
+{
+	path.metadata().and_then(|metadata: Metadata| metadata.modified()).ok()
+}
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 use std::io::Error;
+use std::path::Path;
+use std::path::PathBuf;
 
 use globwalker::GlobError;
 use globwalker::WalkError;
@@ -7,9 +9,65 @@ use globwalker::WalkError;
 #[derive(Debug)]
 pub enum HepheastusError
 {
+	/// A file that is not to be overwritten.
+	AlreadyExists(PathBuf),
+	BuildLocked
+	{
+		path: PathBuf,
+		pid: Option<u32>,
+	},
+	InvalidEnvironmentVariable
+	{
+		name: String,
+		value: String,
+		message: String,
+	},
 	InvalidGlob(GlobError),
+	InvalidRegex(String),
+	InvalidRootDirectory(PathBuf),
+	InvalidSetting
+	{
+		key: String,
+		value: String,
+		message: String,
+	},
 	IoError(Error),
+	DaemonAlreadyRunning(PathBuf),
+	DaemonError(String),
 	DirectoryWalkerError(WalkError),
+	DuplicateOutput(PathBuf),
+	FileSetCycle(Vec<String>),
+	GitIndexError(String),
+	/// A path that has to be written as text, but is not valid UTF-8.
+	NonUtf8Path(PathBuf),
+	ParallelWalkerError(ignore::Error),
+	ParseError
+	{
+		path: PathBuf,
+		line: usize,
+		message: String,
+	},
+	ProfileCycle(Vec<String>),
+	ProtocolError(String),
+	UnknownFileSet(String),
+	UnknownProfile(String),
+	UnknownPool(String),
+}
+
+impl HepheastusError
+{
+	pub fn parse_error(
+		path: &Path,
+		line: usize,
+		message: impl Into<String>,
+	) -> HepheastusError
+	{
+		HepheastusError::ParseError {
+			path: path.to_path_buf(),
+			line,
+			message: message.into(),
+		}
+	}
 }
 
 impl Display for HepheastusError
@@ -21,17 +79,137 @@ impl Display for HepheastusError
 	{
 		match self
 		{
+			Self::AlreadyExists(path) =>
+			{
+				formatter.write_fmt(format_args!(
+					"HephaestusError::AlreadyExists({} exists; use --force to overwrite it)",
+					path.display()
+				))
+			}
+
+			Self::BuildLocked { path, pid } =>
+			{
+				match pid
+				{
+					Option::Some(pid) =>
+					{
+						formatter.write_fmt(format_args!(
+							"HephaestusError::BuildLocked(another build (PID {}) holds {}; use --wait to wait for it)",
+							pid,
+							path.display()
+						))
+					}
+					Option::None =>
+					{
+						formatter.write_fmt(format_args!(
+							"HephaestusError::BuildLocked(another build holds {}; use --wait to wait for it)",
+							path.display()
+						))
+					}
+				}
+			}
+
+			Self::DaemonAlreadyRunning(path) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::DaemonAlreadyRunning({})", path.display()))
+			}
+
+			Self::DaemonError(message) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::DaemonError({})", message))
+			}
+
 			Self::DirectoryWalkerError(error) =>
 			{
 				formatter.write_fmt(format_args!("HephaestusError::DirectoryWalkerError({:?})", error))
 			}
 
+			Self::DuplicateOutput(path) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::DuplicateOutput({})", path.display()))
+			}
+
+			Self::FileSetCycle(names) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::FileSetCycle(@{})", names.join(" -> @")))
+			}
+
+			Self::GitIndexError(message) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::GitIndexError({})", message))
+			}
+
+			Self::InvalidEnvironmentVariable { name, value, message } =>
+			{
+				formatter.write_fmt(format_args!(
+					"HephaestusError::InvalidEnvironmentVariable({}={}: {})",
+					name, value, message
+				))
+			}
+
 			Self::InvalidGlob(glob_error) =>
 			{
 				formatter.write_fmt(format_args!("HephaestusError::InvalidGlob({:?})", glob_error))
 			}
 
+			Self::InvalidRegex(message) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::InvalidRegex({})", message))
+			}
+
+			Self::InvalidRootDirectory(path) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::InvalidRootDirectory({})", path.display()))
+			}
+
+			Self::InvalidSetting { key, value, message } =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::InvalidSetting({} = {}: {})", key, value, message))
+			}
+
 			Self::IoError(error) => formatter.write_fmt(format_args!("HephaestusError::IoError({:?})", error)),
+
+			Self::NonUtf8Path(path) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::NonUtf8Path({} is not valid UTF-8)", path.display()))
+			}
+
+			Self::ParallelWalkerError(error) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::ParallelWalkerError({})", error))
+			}
+
+			Self::ParseError { path, line, message } =>
+			{
+				formatter.write_fmt(format_args!(
+					"HephaestusError::ParseError({}:{}: {})",
+					path.display(),
+					line,
+					message
+				))
+			}
+
+			Self::ProfileCycle(names) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::ProfileCycle({})", names.join(" -> ")))
+			}
+
+			Self::ProtocolError(message) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::ProtocolError({})", message))
+			}
+
+			Self::UnknownFileSet(name) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::UnknownFileSet(@{})", name))
+			}
+
+			Self::UnknownProfile(name) =>
+			{
+				formatter.write_fmt(format_args!("HephaestusError::UnknownProfile({})", name))
+			}
+
+			Self::UnknownPool(name) => formatter.write_fmt(format_args!("HephaestusError::UnknownPool({})", name)),
 		}
 	}
 }
@@ -52,6 +230,14 @@ impl From<Error> for HepheastusError
 	}
 }
 
+impl From<ignore::Error> for HepheastusError
+{
+	fn from(value: ignore::Error) -> Self
+	{
+		HepheastusError::ParallelWalkerError(value)
+	}
+}
+
 impl From<WalkError> for HepheastusError
 {
 	fn from(value: WalkError) -> Self
@@ -0,0 +1,216 @@
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::path::Path;
+
+use memchr::memmem;
+use memmap3::Mmap;
+use regex_automata::meta::Regex;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+/// How many leading bytes decide whether a file is binary; git looks at as many.
+const BINARY_DETECTION_LENGTH: usize = 8000;
+
+/// A condition on what a file contains, checked after globbing and only for regular files.
+#[derive(Clone, Debug)]
+pub enum ContentFilter
+{
+	/// Files containing this byte string anywhere.
+	Contains(Vec<u8>),
+	/// Files in which the regular expression matches; `^` anchors to the start of the file, `(?m)^` to any line.
+	Matches
+	{
+		pattern: String,
+		regex: Regex,
+	},
+	/// Files starting with these bytes, such as `#!` for scripts or `\x7fELF` for executables.
+	StartsWith(Vec<u8>),
+	/// Files with a NUL byte near the start, which is how git tells binary files from text.
+	Binary,
+	Text,
+}
+
+impl ContentFilter
+{
+	pub fn contains(text: impl Into<Vec<u8>>) -> ContentFilter
+	{
+		ContentFilter::Contains(text.into())
+	}
+
+	pub fn matches(pattern: &str) -> Result<ContentFilter, HepheastusError>
+	{
+		let regex: Regex =
+			Regex::new(pattern).map_err(|error| HepheastusError::InvalidRegex(format!("'{}': {}", pattern, error)))?;
+		Result::Ok(ContentFilter::Matches {
+			pattern: pattern.to_string(),
+			regex,
+		})
+	}
+
+	pub fn starts_with(magic: impl Into<Vec<u8>>) -> ContentFilter
+	{
+		ContentFilter::StartsWith(magic.into())
+	}
+
+	/// Files whose first line is a shebang.
+	pub fn shebang() -> ContentFilter
+	{
+		ContentFilter::StartsWith(b"#!".to_vec())
+	}
+
+	/// How many leading bytes of a file this filter looks at, where `None` means the whole file.
+	fn prefix_length(&self) -> Option<usize>
+	{
+		match self
+		{
+			ContentFilter::Contains(_) | ContentFilter::Matches { .. } => Option::None,
+			ContentFilter::StartsWith(magic) => Option::Some(magic.len()),
+			ContentFilter::Binary | ContentFilter::Text => Option::Some(BINARY_DETECTION_LENGTH),
+		}
+	}
+
+	/// Whether `content`, which is at least [`prefix_length`](ContentFilter::prefix_length) bytes long unless the
+	/// file is shorter, passes this filter.
+	fn is_match(
+		&self,
+		content: &[u8],
+	) -> bool
+	{
+		match self
+		{
+			ContentFilter::Contains(text) => memmem::find(content, text).is_some(),
+			ContentFilter::Matches { regex, .. } => regex.is_match(content),
+			ContentFilter::StartsWith(magic) => content.starts_with(magic),
+			ContentFilter::Binary => is_binary(content),
+			ContentFilter::Text => !is_binary(content),
+		}
+	}
+}
+
+impl PartialEq for ContentFilter
+{
+	fn eq(
+		&self,
+		other: &Self,
+	) -> bool
+	{
+		match (self, other)
+		{
+			(ContentFilter::Contains(lhs), ContentFilter::Contains(rhs)) => lhs == rhs,
+			(ContentFilter::Matches { pattern: lhs, .. }, ContentFilter::Matches { pattern: rhs, .. }) => lhs == rhs,
+			(ContentFilter::StartsWith(lhs), ContentFilter::StartsWith(rhs)) => lhs == rhs,
+			(ContentFilter::Binary, ContentFilter::Binary) | (ContentFilter::Text, ContentFilter::Text) => true,
+			_ => false,
+		}
+	}
+}
+
+/// Whether the file at `path` passes every filter in `filters`.
+///
+/// The file is read once, and only as far as the filters need: a few leading bytes when that is all they look at, and
+/// otherwise through a memory map, so that a large file is not copied onto the heap. A file that vanished since it was
+/// found passes none.
+pub fn matches_all(
+	path: &Path,
+	filters: &[ContentFilter],
+) -> Result<bool, HepheastusError>
+{
+	let file: File = match File::open(path)
+	{
+		Result::Ok(file) => file,
+		Result::Err(error) if error.kind() == ErrorKind::NotFound => return Result::Ok(false),
+		Result::Err(error) => return Result::Err(HepheastusError::IoError(error)),
+	};
+	let prefix_length: Option<usize> = filters
+		.iter()
+		.map(ContentFilter::prefix_length)
+		.try_fold(0, |length: usize, prefix_length: Option<usize>| {
+			prefix_length.map(|prefix_length| length.max(prefix_length))
+		});
+	let is_match = |content: &[u8]| -> bool { filters.iter().all(|filter: &ContentFilter| filter.is_match(content)) };
+	match prefix_length
+	{
+		Option::Some(prefix_length) =>
+		{
+			let mut content: Vec<u8> = Vec::new();
+			file.take(prefix_length as u64).read_to_end(&mut content)?;
+			Result::Ok(is_match(&content))
+		}
+		Option::None =>
+		{
+			// SAFETY: the map is only read, and dropped before this returns. A file truncated by another process
+			// while it is mapped can still fault, which is the price of not copying it; sources are rarely rewritten
+			// in the middle of a search.
+			let content: Mmap = unsafe { Mmap::map(&file)? };
+			Result::Ok(is_match(&content))
+		}
+	}
+}
+
+fn is_binary(content: &[u8]) -> bool
+{
+	memchr::memchr(0, &content[..content.len().min(BINARY_DETECTION_LENGTH)]).is_some()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::io::content_filter::ContentFilter;
+	use crate::hilcode::io::file_set::FileSet;
+	use crate::hilcode::io::file_set_glob::FileSetGlob;
+	use crate::hilcode::test::env::TestEnv;
+
+	fn search(
+		root_directory: &Path,
+		filter: ContentFilter,
+	) -> Vec<PathBuf>
+	{
+		let file_set: FileSet = FileSetGlob::new(root_directory, vec!["**/*".to_string()])
+			.with_content_filter(filter)
+			.search("content")
+			.unwrap();
+		file_set.iter().cloned().collect()
+	}
+
+	#[test]
+	fn content_filters()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::create_dir(root_directory.join("src")).unwrap();
+		std::fs::write(root_directory.join("src/lib.rs"), "#[cfg(test)]\nmod tests {\n\t#[test]\n\tfn it() {}\n}\n")
+			.unwrap();
+		std::fs::write(root_directory.join("src/main.rs"), "fn main() {}\n").unwrap();
+		std::fs::write(root_directory.join("run.sh"), "#!/bin/sh\necho '#[test]'\n").unwrap();
+		std::fs::write(root_directory.join("tool"), b"\x7fELF\x02\x01\x01\x00\x00").unwrap();
+		std::fs::write(root_directory.join("empty"), "").unwrap();
+		assert_eq!(
+			vec![PathBuf::from("run.sh"), PathBuf::from("src/lib.rs")],
+			search(root_directory, ContentFilter::contains("#[test]"))
+		);
+		assert_eq!(vec![PathBuf::from("run.sh")], search(root_directory, ContentFilter::shebang()));
+		assert_eq!(
+			vec![PathBuf::from("src/lib.rs")],
+			search(root_directory, ContentFilter::matches(r"(?m)^\s+#\[test\]$").unwrap())
+		);
+		assert_eq!(vec![PathBuf::from("tool")], search(root_directory, ContentFilter::Binary));
+		assert_eq!(
+			vec![PathBuf::from("tool")],
+			search(root_directory, ContentFilter::starts_with(b"\x7fELF".to_vec()))
+		);
+		assert_eq!(4, search(root_directory, ContentFilter::Text).len());
+	}
+
+	#[test]
+	fn invalid_regex()
+	{
+		assert!(ContentFilter::matches("(unclosed").is_err());
+		assert_eq!(ContentFilter::matches("a+").unwrap(), ContentFilter::matches("a+").unwrap());
+	}
+}
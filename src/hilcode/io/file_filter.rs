@@ -0,0 +1,280 @@
+use std::fs::Metadata;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use crate::hilcode::io::file_stat::FileMode;
+use crate::hilcode::io::file_stat::FileSize;
+
+const EXECUTABLE_BITS: u32 = 0o111;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileKind
+{
+	Regular,
+	Directory,
+	Symlink,
+	/// A regular file with any of the execute bits set, whether for its owner, its group or others.
+	Executable,
+}
+
+impl FromStr for FileKind
+{
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err>
+	{
+		match text
+		{
+			"regular" | "file" => Result::Ok(FileKind::Regular),
+			"dir" | "directory" => Result::Ok(FileKind::Directory),
+			"symlink" => Result::Ok(FileKind::Symlink),
+			"executable" => Result::Ok(FileKind::Executable),
+			_ => Result::Err(format!("expected regular, directory, symlink or executable, not '{}'", text)),
+		}
+	}
+}
+
+/// A condition on a file's attributes, checked against the metadata of the path itself, not the target of a link.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileFilter
+{
+	Kind(FileKind),
+	/// Sizes within an inclusive range.
+	Size
+	{
+		min: Option<FileSize>,
+		max: Option<FileSize>,
+	},
+	/// Modification times at most `newer_than` and more than `older_than` before the moment the filter is checked, so
+	/// that a filter kept around, for instance by the daemon, does not drift.
+	Modified
+	{
+		newer_than: Option<Duration>,
+		older_than: Option<Duration>,
+	},
+	/// Permission bits of which all of `set` must be set and all of `clear` must be clear.
+	Mode
+	{
+		set: FileMode,
+		clear: FileMode,
+	},
+}
+
+impl FileFilter
+{
+	/// Files modified less than `age` ago.
+	pub fn modified_within(age: Duration) -> FileFilter
+	{
+		FileFilter::Modified {
+			newer_than: Option::Some(age),
+			older_than: Option::None,
+		}
+	}
+
+	/// Files modified at least `age` ago.
+	pub fn modified_before(age: Duration) -> FileFilter
+	{
+		FileFilter::Modified {
+			newer_than: Option::None,
+			older_than: Option::Some(age),
+		}
+	}
+
+	pub fn matches(
+		&self,
+		metadata: &Metadata,
+	) -> bool
+	{
+		match self
+		{
+			FileFilter::Kind(FileKind::Regular) => metadata.is_file(),
+			FileFilter::Kind(FileKind::Directory) => metadata.is_dir(),
+			FileFilter::Kind(FileKind::Symlink) => metadata.is_symlink(),
+			FileFilter::Kind(FileKind::Executable) =>
+			{
+				metadata.is_file() && FileMode::of(metadata).bits() & EXECUTABLE_BITS != 0
+			}
+			FileFilter::Size { min, max } =>
+			{
+				let file_size: FileSize = FileSize::of(metadata);
+				min.as_ref()
+					.is_none_or(|min: &FileSize| file_size.bytes() >= min.bytes())
+					&& max
+						.as_ref()
+						.is_none_or(|max: &FileSize| file_size.bytes() <= max.bytes())
+			}
+			FileFilter::Modified { newer_than, older_than } =>
+			{
+				match metadata.modified()
+				{
+					Result::Ok(modified) =>
+					{
+						// A modification time in the future counts as just now.
+						let age: Duration = SystemTime::now().duration_since(modified).unwrap_or_default();
+						newer_than.is_none_or(|newer_than: Duration| age <= newer_than)
+							&& older_than.is_none_or(|older_than: Duration| age > older_than)
+					}
+					Result::Err(_) => false,
+				}
+			}
+			FileFilter::Mode { set, clear } =>
+			{
+				let bits: u32 = FileMode::of(metadata).bits();
+				bits & set.bits() == set.bits() && bits & clear.bits() == 0
+			}
+		}
+	}
+}
+
+/// Parses a duration such as `90s`, `30m`, `12h`, `1d` or `2w`; a bare number counts seconds.
+pub fn parse_age(text: &str) -> Result<Duration, String>
+{
+	let text: &str = text.trim();
+	let split: usize = text
+		.find(|character: char| !character.is_ascii_digit())
+		.unwrap_or(text.len());
+	let (number, unit) = text.split_at(split);
+	let number: u64 = number
+		.parse()
+		.map_err(|_| format!("expected a duration like '1d', not '{}'", text))?;
+	let seconds: u64 = match unit.trim()
+	{
+		"" | "s" => 1,
+		"m" => 60,
+		"h" => 60 * 60,
+		"d" => 24 * 60 * 60,
+		"w" => 7 * 24 * 60 * 60,
+		_ => return Result::Err(format!("unknown duration unit in '{}'; use s, m, h, d or w", text)),
+	};
+	number
+		.checked_mul(seconds)
+		.map(Duration::from_secs)
+		.ok_or_else(|| format!("duration '{}' is too long", text))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::fs::Metadata;
+	use std::os::unix::fs::PermissionsExt;
+	use std::path::Path;
+	use std::path::PathBuf;
+	use std::time::Duration;
+	use std::time::SystemTime;
+
+	use crate::hilcode::io::file_filter::FileFilter;
+	use crate::hilcode::io::file_filter::FileKind;
+	use crate::hilcode::io::file_filter::parse_age;
+	use crate::hilcode::io::file_set::FileSet;
+	use crate::hilcode::io::file_set_glob::FileSetGlob;
+	use crate::hilcode::io::file_stat::FileMode;
+	use crate::hilcode::io::file_stat::FileSize;
+	use crate::hilcode::test::env::TestEnv;
+
+	fn search(
+		root_directory: &Path,
+		filters: &[FileFilter],
+	) -> Vec<PathBuf>
+	{
+		let file_set_glob: FileSetGlob = filters.iter().fold(
+			FileSetGlob::new(root_directory, vec!["**/*".to_string()]),
+			|file_set_glob: FileSetGlob, filter: &FileFilter| file_set_glob.with_filter(filter.clone()),
+		);
+		let file_set: FileSet = file_set_glob.search("filtered").unwrap();
+		file_set.iter().cloned().collect()
+	}
+
+	#[test]
+	fn executables_larger_than_a_kibibyte_modified_in_the_last_day()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::create_dir(root_directory.join("bin")).unwrap();
+		for (file, size, mode) in [
+			("bin/tool", 2048, 0o755),
+			("bin/tiny", 16, 0o755),
+			("data.bin", 4096, 0o644),
+		]
+		{
+			let path: PathBuf = root_directory.join(file);
+			std::fs::write(&path, vec![0u8; size]).unwrap();
+			std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+		}
+		std::os::unix::fs::symlink("tool", root_directory.join("bin/link")).unwrap();
+		let filters: Vec<FileFilter> = vec![
+			FileFilter::Kind(FileKind::Executable),
+			FileFilter::Size {
+				min: Option::Some("1KiB".parse().unwrap()),
+				max: Option::None,
+			},
+			FileFilter::modified_within(parse_age("1d").unwrap()),
+		];
+		assert_eq!(vec![PathBuf::from("bin/tool")], search(root_directory, &filters));
+		assert_eq!(vec![PathBuf::from("bin/link")], search(root_directory, &[FileFilter::Kind(FileKind::Symlink)]));
+		assert_eq!(vec![PathBuf::from("bin")], search(root_directory, &[FileFilter::Kind(FileKind::Directory)]));
+		assert!(search(root_directory, &[FileFilter::modified_before(Duration::from_secs(3600))]).is_empty());
+	}
+
+	#[test]
+	fn mode()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let path: PathBuf = test_env.root_directory().join("script.sh");
+		std::fs::write(&path, "#!/bin/sh\n").unwrap();
+		std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o754)).unwrap();
+		let metadata: Metadata = path.symlink_metadata().unwrap();
+		let filter = |set: u32, clear: u32| {
+			FileFilter::Mode {
+				set: FileMode::new(set),
+				clear: FileMode::new(clear),
+			}
+		};
+		assert!(filter(0o100, 0o002).matches(&metadata));
+		assert!(!filter(0o001, 0).matches(&metadata));
+		assert!(!filter(0, 0o004).matches(&metadata));
+		assert!(FileFilter::Kind(FileKind::Regular).matches(&metadata));
+		assert!(
+			FileFilter::Size {
+				min: Option::Some(FileSize::new(10)),
+				max: Option::Some(FileSize::new(10)),
+			}
+			.matches(&metadata)
+		);
+	}
+
+	#[test]
+	fn ages_are_measured_when_matching()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let path: PathBuf = test_env.root_directory().join("new.txt");
+		let older_than_nothing: FileFilter = FileFilter::modified_before(Duration::ZERO);
+		let within_a_second: FileFilter = FileFilter::modified_within(Duration::from_secs(1));
+		std::thread::sleep(Duration::from_millis(20));
+		std::fs::write(&path, "").unwrap();
+		std::thread::sleep(Duration::from_millis(20));
+		let metadata: Metadata = path.symlink_metadata().unwrap();
+		assert!(older_than_nothing.matches(&metadata));
+		assert!(within_a_second.matches(&metadata));
+		std::fs::File::options()
+			.write(true)
+			.open(&path)
+			.unwrap()
+			.set_modified(SystemTime::now() - Duration::from_secs(60))
+			.unwrap();
+		assert!(!within_a_second.matches(&path.symlink_metadata().unwrap()));
+	}
+
+	#[test]
+	fn ages()
+	{
+		assert_eq!(Duration::from_secs(90), parse_age("90").unwrap());
+		assert_eq!(Duration::from_secs(30 * 60), parse_age("30m").unwrap());
+		assert_eq!(Duration::from_secs(2 * 7 * 24 * 3600), parse_age("2w").unwrap());
+		assert!(parse_age("1y").is_err());
+		assert!(parse_age("d").is_err());
+		assert!(parse_age("99999999999999999w").is_err());
+	}
+}
@@ -0,0 +1,130 @@
+use std::ffi::OsStr;
+use std::io::Read;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::io::file_set::FileSet;
+use crate::hilcode::io::git_index::GitIndex;
+
+/// Where a file set takes a ready-made list of files from, so that hephaestus can work with the output of other tools.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileListSource
+{
+	/// The files git tracks below a directory, as `git ls-files` would list them from there.
+	GitIndex(PathBuf),
+	/// A file with one path per line, or NUL-separated paths, relative to `base_directory` unless absolute.
+	ListFile
+	{
+		path: PathBuf, base_directory: PathBuf
+	},
+	/// Paths read from standard input, as in a list file.
+	Stdin(PathBuf),
+}
+
+impl FileListSource
+{
+	pub fn read(
+		&self,
+		name: &str,
+	) -> Result<FileSet, HepheastusError>
+	{
+		match self
+		{
+			FileListSource::GitIndex(directory) =>
+			{
+				let files: Vec<PathBuf> = GitIndex::load(directory)?.files_below(directory)?;
+				Result::Ok(FileSet::new(name, directory, files))
+			}
+			FileListSource::ListFile { path, base_directory } =>
+			{
+				let content: Vec<u8> = std::fs::read(path)?;
+				Result::Ok(FileSet::new(name, base_directory, parse_file_list(&content)))
+			}
+			FileListSource::Stdin(base_directory) => FileListSource::read_from(name, base_directory, std::io::stdin()),
+		}
+	}
+
+	/// A file set of the paths listed in whatever `reader` yields.
+	pub fn read_from(
+		name: &str,
+		base_directory: &Path,
+		mut reader: impl Read,
+	) -> Result<FileSet, HepheastusError>
+	{
+		let mut content: Vec<u8> = Vec::new();
+		reader.read_to_end(&mut content)?;
+		Result::Ok(FileSet::new(name, base_directory, parse_file_list(&content)))
+	}
+}
+
+/// The paths in `content`: NUL-separated if it holds a NUL byte, as from `find -print0` or `git ls-files -z`, and
+/// otherwise one per line. Empty entries are skipped, and so is the carriage return of a CRLF line ending.
+pub fn parse_file_list(content: &[u8]) -> Vec<PathBuf>
+{
+	let separator: u8 = if memchr::memchr(0, content).is_some() { 0 } else { b'\n' };
+	content
+		.split(|byte: &u8| *byte == separator)
+		.map(|entry: &[u8]| {
+			if separator == b'\n'
+			{
+				entry.strip_suffix(b"\r").unwrap_or(entry)
+			}
+			else
+			{
+				entry
+			}
+		})
+		.filter(|entry: &&[u8]| !entry.is_empty())
+		.map(|entry: &[u8]| PathBuf::from(OsStr::from_bytes(entry)))
+		.collect()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::io::file_list::FileListSource;
+	use crate::hilcode::io::file_list::parse_file_list;
+	use crate::hilcode::io::file_set::FileSet;
+	use crate::hilcode::test::env::TestEnv;
+
+	#[test]
+	fn separators()
+	{
+		let expected: Vec<PathBuf> = vec![PathBuf::from("src/main.rs"), PathBuf::from("with\nnewline.rs")];
+		assert_eq!(expected, parse_file_list(b"src/main.rs\0with\nnewline.rs\0"));
+		assert_eq!(vec![PathBuf::from("a.rs"), PathBuf::from("b c.rs")], parse_file_list(b"a.rs\r\n\nb c.rs"));
+		assert!(parse_file_list(b"").is_empty());
+	}
+
+	#[test]
+	fn sources()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::write(root_directory.join("files.txt"), "b.rs\n./a.rs\nsub/../b.rs\n").unwrap();
+		let list_file: FileListSource = FileListSource::ListFile {
+			path: root_directory.join("files.txt"),
+			base_directory: root_directory.to_path_buf(),
+		};
+		let file_set: FileSet = list_file.read("listed").unwrap();
+		assert_eq!(vec!["a.rs", "b.rs"], file_set.display_paths().collect::<Vec<String>>());
+		let input: String = format!("{}\n", root_directory.join("c.rs").display());
+		let file_set: FileSet = FileListSource::read_from("piped", root_directory, input.as_bytes()).unwrap();
+		assert_eq!(vec![PathBuf::from("c.rs")], file_set.iter().cloned().collect::<Vec<PathBuf>>());
+		std::fs::create_dir(root_directory.join(".git")).unwrap();
+		std::fs::write(root_directory.join(".git/index"), b"DIRC\0\0\0\x02\0\0\0\0").unwrap();
+		assert!(
+			FileListSource::GitIndex(root_directory.to_path_buf())
+				.read("tracked")
+				.unwrap()
+				.is_empty()
+		);
+	}
+}
@@ -1,26 +1,321 @@
+use std::borrow::Cow;
+use std::path::Component;
+use std::path::Path;
 use std::path::PathBuf;
 
-#[derive(Debug)]
+use crate::hilcode::io::normal_path;
+
+/// A named set of paths below a base directory, kept sorted and free of duplicates.
+///
+/// The base directory is kept absolute and the paths relative to it, both normalised, so the same files make the same
+/// set however the base directory was spelled, and the relative paths read the same on every machine.
+#[derive(Clone, Debug, PartialEq)]
 pub struct FileSet
 {
 	name: String,
+	base_directory: PathBuf,
 	files: Vec<PathBuf>,
 }
 
 impl FileSet
 {
+	/// Creates a file set from `files`, which may be absolute or relative to `base_directory`.
 	pub fn new(
 		name: impl Into<String>,
+		base_directory: &Path,
 		files: impl Into<Vec<PathBuf>>,
 	) -> FileSet
 	{
 		let name: String = name.into();
-		let files: Vec<PathBuf> = files.into();
-		FileSet { name, files }
+		let base_directory: PathBuf = normal_path::absolute(base_directory);
+		let mut files: Vec<PathBuf> = files
+			.into()
+			.iter()
+			.map(|file: &PathBuf| relative_file(&base_directory, file))
+			.collect();
+		files.sort();
+		files.dedup();
+		FileSet {
+			name,
+			base_directory,
+			files,
+		}
+	}
+
+	pub fn name(&self) -> &str
+	{
+		&self.name
 	}
 
+	/// The same files under another name.
+	pub fn renamed(
+		&self,
+		name: impl Into<String>,
+	) -> FileSet
+	{
+		FileSet {
+			name: name.into(),
+			..self.clone()
+		}
+	}
+
+	pub fn base_directory(&self) -> &Path
+	{
+		&self.base_directory
+	}
+
+	/// The paths relative to the base directory.
 	pub fn iter(&self) -> impl Iterator<Item = &PathBuf>
 	{
 		self.files.iter()
 	}
+
+	/// The absolute paths.
+	pub fn absolute_paths(&self) -> impl Iterator<Item = PathBuf>
+	{
+		self.files.iter().map(|file: &PathBuf| self.absolute(file))
+	}
+
+	/// The absolute form of `file`, a path relative to the base directory.
+	pub fn absolute(
+		&self,
+		file: &Path,
+	) -> PathBuf
+	{
+		normal_path::normalise(&self.base_directory.join(file))
+	}
+
+	/// The paths as shown in output and manifests: relative to the base directory, with `/` between components.
+	pub fn display_paths(&self) -> impl Iterator<Item = String>
+	{
+		self.files.iter().map(|file: &PathBuf| {
+			file.components()
+				.map(|component: Component| component.as_os_str().to_string_lossy())
+				.collect::<Vec<Cow<str>>>()
+				.join("/")
+		})
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.files.len()
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.files.is_empty()
+	}
+
+	/// Whether `path`, absolute or relative to the base directory, is in this set.
+	pub fn contains(
+		&self,
+		path: &Path,
+	) -> bool
+	{
+		let path: PathBuf = relative_file(&self.base_directory, path);
+		self.files.binary_search(&path).is_ok()
+	}
+
+	/// The paths in `self`, in `other` or in both.
+	pub fn union(
+		&self,
+		name: impl Into<String>,
+		other: &FileSet,
+	) -> FileSet
+	{
+		self.merge(name, other, true, true, true)
+	}
+
+	/// The paths in both `self` and `other`.
+	pub fn intersection(
+		&self,
+		name: impl Into<String>,
+		other: &FileSet,
+	) -> FileSet
+	{
+		self.merge(name, other, false, true, false)
+	}
+
+	/// The paths in `self` that are not in `other`.
+	pub fn difference(
+		&self,
+		name: impl Into<String>,
+		other: &FileSet,
+	) -> FileSet
+	{
+		self.merge(name, other, true, false, false)
+	}
+
+	/// The paths in exactly one of `self` and `other`.
+	pub fn symmetric_difference(
+		&self,
+		name: impl Into<String>,
+		other: &FileSet,
+	) -> FileSet
+	{
+		self.merge(name, other, true, false, true)
+	}
+
+	/// Walks both sorted lists in step, keeping the paths found only in `self`, in both, or only in `other`, as asked.
+	///
+	/// The result has the base directory of `self`; the paths of `other` are rebased onto it first if need be.
+	fn merge(
+		&self,
+		name: impl Into<String>,
+		other: &FileSet,
+		only_self: bool,
+		both: bool,
+		only_other: bool,
+	) -> FileSet
+	{
+		let rebased: FileSet;
+		let other: &FileSet = match other.base_directory == self.base_directory
+		{
+			true => other,
+			false =>
+			{
+				rebased =
+					FileSet::new(other.name(), &self.base_directory, other.absolute_paths().collect::<Vec<PathBuf>>());
+				&rebased
+			}
+		};
+		let mut files: Vec<PathBuf> = Vec::new();
+		let mut lhs: usize = 0;
+		let mut rhs: usize = 0;
+		while lhs < self.files.len() || rhs < other.files.len()
+		{
+			let (file, keep): (&PathBuf, bool) = match (self.files.get(lhs), other.files.get(rhs))
+			{
+				(Option::Some(lhs_file), Option::Some(rhs_file)) if lhs_file == rhs_file =>
+				{
+					lhs += 1;
+					rhs += 1;
+					(lhs_file, both)
+				}
+				(Option::Some(lhs_file), Option::Some(rhs_file)) if lhs_file < rhs_file =>
+				{
+					lhs += 1;
+					(lhs_file, only_self)
+				}
+				(Option::Some(lhs_file), Option::None) =>
+				{
+					lhs += 1;
+					(lhs_file, only_self)
+				}
+				(_, Option::Some(rhs_file)) =>
+				{
+					rhs += 1;
+					(rhs_file, only_other)
+				}
+				(Option::None, Option::None) => break,
+			};
+			if keep
+			{
+				files.push(file.clone());
+			}
+		}
+		FileSet {
+			name: name.into(),
+			base_directory: self.base_directory.clone(),
+			files,
+		}
+	}
+}
+
+fn relative_file(
+	base_directory: &Path,
+	file: &Path,
+) -> PathBuf
+{
+	normal_path::relative_to(&normal_path::normalise(&base_directory.join(file)), base_directory)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::io::file_set::FileSet;
+
+	fn file_set(
+		name: &str,
+		files: &[&str],
+	) -> FileSet
+	{
+		FileSet::new(name, Path::new("/project"), files.iter().map(PathBuf::from).collect::<Vec<PathBuf>>())
+	}
+
+	fn files_of(file_set: &FileSet) -> Vec<&str>
+	{
+		file_set.iter().map(|file: &PathBuf| file.to_str().unwrap()).collect()
+	}
+
+	#[test]
+	fn new_sorts_and_removes_duplicates()
+	{
+		let sources: FileSet = file_set("sources", &["src/main.rs", "build.rs", "src/main.rs", "src/lib.rs"]);
+		assert_eq!(vec!["build.rs", "src/lib.rs", "src/main.rs"], files_of(&sources));
+		assert_eq!(3, sources.len());
+		assert!(sources.contains(Path::new("src/lib.rs")));
+		assert!(!sources.contains(Path::new("src/test.rs")));
+		assert!(FileSet::new("empty", Path::new("/project"), Vec::new()).is_empty());
+	}
+
+	#[test]
+	fn relative_and_absolute_paths()
+	{
+		let files: Vec<PathBuf> = vec![
+			PathBuf::from("/work/teng/src/main.rs"),
+			PathBuf::from("./src/bin/../lib.rs"),
+			PathBuf::from("src/lib.rs"),
+		];
+		let sources: FileSet = FileSet::new("sources", Path::new("/work/hephaestus/../teng/."), files);
+		assert_eq!(Path::new("/work/teng"), sources.base_directory());
+		assert_eq!(vec!["src/lib.rs", "src/main.rs"], files_of(&sources));
+		assert_eq!(
+			vec![
+				PathBuf::from("/work/teng/src/lib.rs"),
+				PathBuf::from("/work/teng/src/main.rs")
+			],
+			sources.absolute_paths().collect::<Vec<PathBuf>>()
+		);
+		assert!(sources.contains(Path::new("/work/teng/src/main.rs")));
+		assert!(sources.contains(Path::new("src/../src/main.rs")));
+		assert_eq!(vec!["src/lib.rs", "src/main.rs"], sources.display_paths().collect::<Vec<String>>());
+	}
+
+	#[test]
+	fn set_algebra_across_base_directories()
+	{
+		let sources: FileSet = FileSet::new(
+			"sources",
+			Path::new("/work/teng"),
+			vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/main.rs")],
+		);
+		let tests: FileSet = FileSet::new("tests", Path::new("/work/teng/src"), vec![PathBuf::from("main.rs")]);
+		assert_eq!(vec!["src/lib.rs"], files_of(&sources.difference("difference", &tests)));
+	}
+
+	#[test]
+	fn set_algebra()
+	{
+		let sources: FileSet = file_set("sources", &["a.rs", "b.rs", "c.rs", "d.rs"]);
+		let generated: FileSet = file_set("generated", &["b.rs", "d.rs", "e.rs"]);
+		let union: FileSet = sources.union("union", &generated);
+		assert_eq!("union", union.name());
+		assert_eq!(vec!["a.rs", "b.rs", "c.rs", "d.rs", "e.rs"], files_of(&union));
+		assert_eq!(vec!["b.rs", "d.rs"], files_of(&sources.intersection("intersection", &generated)));
+		assert_eq!(vec!["a.rs", "c.rs"], files_of(&sources.difference("difference", &generated)));
+		assert_eq!(vec!["e.rs"], files_of(&generated.difference("difference", &sources)));
+		assert_eq!(
+			vec!["a.rs", "c.rs", "e.rs"],
+			files_of(&sources.symmetric_difference("symmetric-difference", &generated))
+		);
+		let empty: FileSet = FileSet::new("empty", Path::new("/project"), Vec::new());
+		assert_eq!(files_of(&sources), files_of(&sources.union("union", &empty)));
+		assert!(sources.intersection("intersection", &empty).is_empty());
+	}
 }
@@ -0,0 +1,195 @@
+use std::path::Path;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+/// A file set built from other, named file sets, such as `@rust-sources - (@tests + @benches)`.
+///
+/// The operators are `+` (union), `&` (intersection), `-` (difference) and `^` (symmetric difference). They all bind
+/// equally tightly and apply from left to right; parentheses group. Because names may contain `-`, an operator that
+/// follows a name must be preceded by white space.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileSetExpression
+{
+	Reference(String),
+	Union(Box<FileSetExpression>, Box<FileSetExpression>),
+	Intersection(Box<FileSetExpression>, Box<FileSetExpression>),
+	Difference(Box<FileSetExpression>, Box<FileSetExpression>),
+	SymmetricDifference(Box<FileSetExpression>, Box<FileSetExpression>),
+}
+
+impl FileSetExpression
+{
+	/// Parses `text`, reporting errors against `line` of the file at `path`.
+	pub fn parse(
+		path: &Path,
+		line: usize,
+		text: &str,
+	) -> Result<FileSetExpression, HepheastusError>
+	{
+		let mut parser: Parser = Parser { text, position: 0 };
+		let file_set_expression: Result<FileSetExpression, String> = parser.expression().and_then(|expression| {
+			parser.skip_white_space();
+			match parser.peek()
+			{
+				Option::None => Result::Ok(expression),
+				Option::Some(character) => Result::Err(format!("unexpected '{}'", character)),
+			}
+		});
+		file_set_expression.map_err(|message: String| {
+			HepheastusError::parse_error(path, line, format!("{} in file set expression '{}'", message, text))
+		})
+	}
+
+	/// The names this expression refers to, in order of appearance.
+	pub fn references(&self) -> Vec<&str>
+	{
+		match self
+		{
+			FileSetExpression::Reference(name) => vec![name.as_str()],
+			FileSetExpression::Union(lhs, rhs)
+			| FileSetExpression::Intersection(lhs, rhs)
+			| FileSetExpression::Difference(lhs, rhs)
+			| FileSetExpression::SymmetricDifference(lhs, rhs) =>
+			{
+				let mut references: Vec<&str> = lhs.references();
+				references.extend(rhs.references());
+				references
+			}
+		}
+	}
+}
+
+struct Parser<'text>
+{
+	text: &'text str,
+	position: usize,
+}
+
+impl Parser<'_>
+{
+	fn expression(&mut self) -> Result<FileSetExpression, String>
+	{
+		let mut expression: FileSetExpression = self.term()?;
+		loop
+		{
+			self.skip_white_space();
+			let operator: fn(Box<FileSetExpression>, Box<FileSetExpression>) -> FileSetExpression = match self.peek()
+			{
+				Option::Some('+') => FileSetExpression::Union,
+				Option::Some('&') => FileSetExpression::Intersection,
+				Option::Some('-') => FileSetExpression::Difference,
+				Option::Some('^') => FileSetExpression::SymmetricDifference,
+				_ => return Result::Ok(expression),
+			};
+			self.position += 1;
+			expression = operator(Box::new(expression), Box::new(self.term()?));
+		}
+	}
+
+	fn term(&mut self) -> Result<FileSetExpression, String>
+	{
+		self.skip_white_space();
+		match self.peek()
+		{
+			Option::Some('@') =>
+			{
+				self.position += 1;
+				let name: &str = &self.text[self.position..];
+				let length: usize = name
+					.find(|character: char| !is_name_character(character))
+					.unwrap_or(name.len());
+				if length == 0
+				{
+					return Result::Err("missing file set name after '@'".to_string());
+				}
+				self.position += length;
+				Result::Ok(FileSetExpression::Reference(name[..length].to_string()))
+			}
+			Option::Some('(') =>
+			{
+				self.position += 1;
+				let expression: FileSetExpression = self.expression()?;
+				self.skip_white_space();
+				if self.peek() != Option::Some(')')
+				{
+					return Result::Err("missing ')'".to_string());
+				}
+				self.position += 1;
+				Result::Ok(expression)
+			}
+			Option::Some(character) => Result::Err(format!("expected '@name' or '(', not '{}'", character)),
+			Option::None => Result::Err("expected '@name' or '(' at the end".to_string()),
+		}
+	}
+
+	fn peek(&self) -> Option<char>
+	{
+		self.text[self.position..].chars().next()
+	}
+
+	fn skip_white_space(&mut self)
+	{
+		let rest: &str = &self.text[self.position..];
+		self.position += rest.len() - rest.trim_start().len();
+	}
+}
+
+fn is_name_character(character: char) -> bool
+{
+	character.is_alphanumeric() || matches!(character, '-' | '_' | '.')
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+
+	use crate::hilcode::io::file_set_expression::FileSetExpression;
+
+	fn parse(text: &str) -> FileSetExpression
+	{
+		FileSetExpression::parse(Path::new("hephaestus.build"), 1, text).unwrap()
+	}
+
+	fn reference(name: &str) -> Box<FileSetExpression>
+	{
+		Box::new(FileSetExpression::Reference(name.to_string()))
+	}
+
+	#[test]
+	fn left_to_right()
+	{
+		assert_eq!(
+			FileSetExpression::Union(
+				Box::new(FileSetExpression::Difference(reference("rust-sources"), reference("tests"))),
+				reference("build.rs"),
+			),
+			parse("@rust-sources - @tests + @build.rs")
+		);
+	}
+
+	#[test]
+	fn parentheses()
+	{
+		let expression: FileSetExpression = parse("@all^(@a&@b)");
+		assert_eq!(
+			FileSetExpression::SymmetricDifference(
+				reference("all"),
+				Box::new(FileSetExpression::Intersection(reference("a"), reference("b"))),
+			),
+			expression
+		);
+		assert_eq!(vec!["all", "a", "b"], expression.references());
+	}
+
+	#[test]
+	fn errors()
+	{
+		for text in ["", "@", "sources", "@a -", "(@a", "@a @b", "@a)"]
+		{
+			assert!(FileSetExpression::parse(Path::new("hephaestus.build"), 1, text).is_err(), "{}", text);
+		}
+	}
+}
@@ -1,18 +1,47 @@
 use std::cmp::Ordering;
+use std::fs::Metadata;
+use std::os::unix::fs::MetadataExt;
+use std::path::Component;
+use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
 
-use globwalker::DirEntry;
+use globwalker::FileType;
 use globwalker::GlobWalker;
 use globwalker::GlobWalkerBuilder;
-use globwalker::WalkError;
+use ignore::Match;
+use ignore::WalkBuilder;
+use ignore::WalkState;
+use ignore::overrides::Glob;
+use ignore::overrides::Override;
+use ignore::overrides::OverrideBuilder;
+use walkdir::DirEntry;
+use walkdir::WalkDir;
 
 use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::io::content_filter;
+use crate::hilcode::io::content_filter::ContentFilter;
+use crate::hilcode::io::file_filter::FileFilter;
 use crate::hilcode::io::file_set::FileSet;
+use crate::hilcode::io::git_ignore::GitIgnore;
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct FileSetGlob
 {
 	base_directory: PathBuf,
 	globs: Vec<String>,
+	git_ignore: bool,
+	filters: Vec<FileFilter>,
+	content_filters: Vec<ContentFilter>,
+	follow_links: bool,
+	hidden: bool,
+	min_depth: usize,
+	max_depth: usize,
+	one_file_system: bool,
+	directories: bool,
+	error_policy: ErrorPolicy,
+	threads: usize,
 }
 
 impl FileSetGlob
@@ -24,25 +53,443 @@ impl FileSetGlob
 	{
 		let base_directory: PathBuf = base_directory.into();
 		let globs: Vec<String> = globs.into();
-		FileSetGlob { base_directory, globs }
+		FileSetGlob {
+			base_directory,
+			globs,
+			git_ignore: false,
+			filters: Vec::new(),
+			content_filters: Vec::new(),
+			follow_links: false,
+			hidden: true,
+			min_depth: 0,
+			max_depth: usize::MAX,
+			one_file_system: false,
+			directories: true,
+			error_policy: ErrorPolicy::Abort,
+			threads: 1,
+		}
 	}
 
+	/// Leaves out whatever git would ignore: see [`GitIgnore`] for where the patterns come from.
+	pub fn with_git_ignore(
+		self,
+		git_ignore: bool,
+	) -> Self
+	{
+		FileSetGlob { git_ignore, ..self }
+	}
+
+	/// Keeps only the paths that `filter` matches, on top of any filters added before.
+	pub fn with_filter(
+		mut self,
+		filter: FileFilter,
+	) -> Self
+	{
+		self.filters.push(filter);
+		self
+	}
+
+	/// Keeps only the regular files whose content `content_filter` matches, on top of any filters added before.
+	pub fn with_content_filter(
+		mut self,
+		content_filter: ContentFilter,
+	) -> Self
+	{
+		self.content_filters.push(content_filter);
+		self
+	}
+
+	/// Enters symbolic links to directories; a link back to a directory being walked is not entered again.
+	pub fn with_follow_links(
+		self,
+		follow_links: bool,
+	) -> Self
+	{
+		FileSetGlob { follow_links, ..self }
+	}
+
+	/// Whether to find paths with a component below the base directory that starts with a `.`.
+	pub fn with_hidden(
+		self,
+		hidden: bool,
+	) -> Self
+	{
+		FileSetGlob { hidden, ..self }
+	}
+
+	/// Only finds paths at least `min_depth` and at most `max_depth` levels below the base directory, where the
+	/// base directory's own entries are at depth 1.
+	pub fn with_depth(
+		self,
+		min_depth: usize,
+		max_depth: usize,
+	) -> Self
+	{
+		FileSetGlob {
+			min_depth,
+			max_depth,
+			..self
+		}
+	}
+
+	/// Stays on the file system of the base directory, like `find -xdev`.
+	pub fn with_one_file_system(
+		self,
+		one_file_system: bool,
+	) -> Self
+	{
+		FileSetGlob {
+			one_file_system,
+			..self
+		}
+	}
+
+	/// Whether directories themselves are part of the result, rather than only what they contain.
+	pub fn with_directories(
+		self,
+		directories: bool,
+	) -> Self
+	{
+		FileSetGlob { directories, ..self }
+	}
+
+	/// What to do about a path that cannot be read, for instance because permission was denied or it vanished while
+	/// being walked.
+	pub fn with_error_policy(
+		self,
+		error_policy: ErrorPolicy,
+	) -> Self
+	{
+		FileSetGlob { error_policy, ..self }
+	}
+
+	/// Searches with `threads` threads; the result is sorted afterwards, so it is the same as with one thread.
+	///
+	/// Several threads walk with the `ignore` crate, but decide about each path with the same globs and the same
+	/// [`GitIgnore`] as one thread does, so the number of threads never changes what is found.
+	pub fn with_threads(
+		self,
+		threads: usize,
+	) -> Self
+	{
+		FileSetGlob { threads, ..self }
+	}
+
+	/// Finds the matching paths, failing on the first path that cannot be read unless the error policy says otherwise.
 	pub fn search(
 		&self,
 		name: impl Into<String>,
 	) -> Result<FileSet, HepheastusError>
 	{
-		let globe_walker: GlobWalker = GlobWalkerBuilder::from_patterns(&self.base_directory, &self.globs)
+		let (file_set, _) = self.search_with_errors(name)?;
+		Result::Ok(file_set)
+	}
+
+	/// Like [`search`](FileSetGlob::search), but also returns the errors collected under [`ErrorPolicy::Collect`].
+	pub fn search_with_errors(
+		&self,
+		name: impl Into<String>,
+	) -> Result<(FileSet, Vec<HepheastusError>), HepheastusError>
+	{
+		if self.threads > 1
+		{
+			return self.search_parallel(name);
+		}
+		let mut files: Vec<PathBuf> = Vec::new();
+		let mut errors: Vec<HepheastusError> = Vec::new();
+		for path in self.iter()?
+		{
+			match (path, self.error_policy)
+			{
+				(Result::Ok(path), _) => files.push(path),
+				(Result::Err(error), ErrorPolicy::Abort) => return Result::Err(error),
+				(Result::Err(error), ErrorPolicy::Collect) => errors.push(error),
+			}
+		}
+		Result::Ok((FileSet::new(name, &self.base_directory, files), errors))
+	}
+
+	fn search_parallel(
+		&self,
+		name: impl Into<String>,
+	) -> Result<(FileSet, Vec<HepheastusError>), HepheastusError>
+	{
+		let globs: Override = self.compile_globs()?;
+		let git_ignore: Option<Mutex<GitIgnore>> = match self.git_ignore
+		{
+			true => Option::Some(Mutex::new(GitIgnore::new(&self.base_directory)?)),
+			false => Option::None,
+		};
+		let mut walk_builder: WalkBuilder = WalkBuilder::new(&self.base_directory);
+		walk_builder
+			.threads(self.threads)
+			.standard_filters(false)
+			.hidden(!self.hidden)
+			.follow_links(self.follow_links)
+			.max_depth(Option::Some(self.max_depth))
+			.same_file_system(self.one_file_system);
+		let found: Mutex<Vec<Found>> = Mutex::new(Vec::new());
+		// Under `ErrorPolicy::Abort` the search fails with the error a sequential search would run into first, which is
+		// the one for the smallest path; only the paths after the smallest error so far are left unwalked.
+		let first_error: Mutex<Option<PathBuf>> = Mutex::new(Option::None);
+		walk_builder.build_parallel().run(|| {
+			Box::new(|dir_entry: Result<ignore::DirEntry, ignore::Error>| -> WalkState {
+				let (path, walk_state): (Option<Found>, WalkState) = match dir_entry
+				{
+					Result::Ok(dir_entry) if is_after(&first_error, dir_entry.path()) =>
+					{
+						(Option::None, WalkState::Skip)
+					}
+					Result::Ok(dir_entry) => self.visit(&globs, git_ignore.as_ref(), dir_entry),
+					Result::Err(error) if is_loop(&error) => (Option::None, WalkState::Continue),
+					Result::Err(error) =>
+					{
+						let path: PathBuf = error_path(&error).unwrap_or(&self.base_directory).to_path_buf();
+						(
+							Option::Some(Result::Err((path, HepheastusError::ParallelWalkerError(error)))),
+							WalkState::Continue,
+						)
+					}
+				};
+				if let Option::Some(Result::Err((path, _))) = &path
+					&& self.error_policy == ErrorPolicy::Abort
+				{
+					let mut first_error: MutexGuard<Option<PathBuf>> = first_error.lock().unwrap();
+					if first_error
+						.as_ref()
+						.is_none_or(|first_error: &PathBuf| path < first_error)
+					{
+						*first_error = Option::Some(path.clone());
+					}
+				}
+				if let Option::Some(path) = path
+				{
+					found.lock().unwrap().push(path);
+				}
+				walk_state
+			})
+		});
+		let mut files: Vec<PathBuf> = Vec::new();
+		let mut errors: Vec<(PathBuf, HepheastusError)> = Vec::new();
+		for path in found.into_inner().unwrap()
+		{
+			match path
+			{
+				Result::Ok(path) => files.push(path),
+				Result::Err(error) => errors.push(error),
+			}
+		}
+		// The threads report errors in whatever order they run into them; sort them by path so that the order of the
+		// collected ones is that of a sequential search.
+		errors.sort_by(|(lhs, _): &(PathBuf, HepheastusError), (rhs, _): &(PathBuf, HepheastusError)| lhs.cmp(rhs));
+		let mut errors: Vec<HepheastusError> = errors
+			.into_iter()
+			.map(|(_, error): (PathBuf, HepheastusError)| error)
+			.collect();
+		if self.error_policy == ErrorPolicy::Abort && !errors.is_empty()
+		{
+			return Result::Err(errors.swap_remove(0));
+		}
+		Result::Ok((FileSet::new(name, &self.base_directory, files), errors))
+	}
+
+	/// Decides about one entry of a parallel walk the way [`FileSetIter`] does for a sequential one.
+	fn visit(
+		&self,
+		globs: &Override,
+		git_ignore: Option<&Mutex<GitIgnore>>,
+		dir_entry: ignore::DirEntry,
+	) -> (Option<Found>, WalkState)
+	{
+		let is_dir: bool = dir_entry
+			.file_type()
+			.is_some_and(|file_type: std::fs::FileType| file_type.is_dir());
+		let path: &Path = dir_entry.path();
+		let relative_path: &Path = path.strip_prefix(&self.base_directory).unwrap_or(path);
+		if relative_path.as_os_str().is_empty()
+		{
+			return (Option::None, WalkState::Continue);
+		}
+		let matched: Match<Glob> = globs.matched(relative_path, is_dir);
+		if matched.is_ignore() && is_dir
+		{
+			return (Option::None, WalkState::Skip);
+		}
+		if !is_dir && !matched.is_whitelist()
+		{
+			return (Option::None, WalkState::Continue);
+		}
+		if git_ignore.is_some_and(|git_ignore: &Mutex<GitIgnore>| git_ignore.lock().unwrap().is_ignored(path, is_dir))
+		{
+			return (Option::None, if is_dir { WalkState::Skip } else { WalkState::Continue });
+		}
+		let is_file: bool = dir_entry
+			.file_type()
+			.is_some_and(|file_type: std::fs::FileType| file_type.is_file());
+		let is_wanted: bool = match is_dir
+		{
+			true => self.directories,
+			false =>
+			{
+				dir_entry
+					.file_type()
+					.is_some_and(|file_type: std::fs::FileType| file_type.is_file() || file_type.is_symlink())
+			}
+		};
+		if !matched.is_whitelist() || !is_wanted || dir_entry.depth() < self.min_depth
+		{
+			return (Option::None, WalkState::Continue);
+		}
+		let passes: Result<bool, HepheastusError> =
+			self.passes_filters(path, is_file, || dir_entry.metadata().map_err(HepheastusError::ParallelWalkerError));
+		match passes
+		{
+			Result::Ok(true) => (Option::Some(Result::Ok(dir_entry.into_path())), WalkState::Continue),
+			Result::Ok(false) => (Option::None, WalkState::Continue),
+			Result::Err(error) => (Option::Some(Result::Err((dir_entry.into_path(), error))), WalkState::Continue),
+		}
+	}
+
+	/// Walks lazily, yielding each matching path as it is found, in the order a search sorts them.
+	///
+	/// Under [`ErrorPolicy::Abort`] the iterator ends after the first error; under [`ErrorPolicy::Collect`] it yields
+	/// each error in place of the path that caused it and carries on.
+	pub fn iter(&self) -> Result<FileSetIter<'_>, HepheastusError>
+	{
+		let globs: Override = self.compile_globs()?;
+		let walker: walkdir::IntoIter = WalkDir::new(&self.base_directory)
+			.follow_links(self.follow_links)
+			.max_depth(self.max_depth)
+			.sort_by(FileSetGlob::cmp)
+			.into_iter();
+		let device: Option<u64> = match self.one_file_system
+		{
+			true => Option::Some(self.base_directory.metadata()?.dev()),
+			false => Option::None,
+		};
+		let git_ignore: Option<GitIgnore> = match self.git_ignore
+		{
+			true => Option::Some(GitIgnore::new(&self.base_directory)?),
+			false => Option::None,
+		};
+		Result::Ok(FileSetIter {
+			file_set_glob: self,
+			globs,
+			walker,
+			device,
+			git_ignore,
+			done: false,
+		})
+	}
+
+	pub fn base_directory(&self) -> &Path
+	{
+		&self.base_directory
+	}
+
+	pub fn globs(&self) -> &[String]
+	{
+		&self.globs
+	}
+
+	pub fn git_ignore(&self) -> bool
+	{
+		self.git_ignore
+	}
+
+	/// The base directory and every directory below it that a search would enter.
+	///
+	/// A search can only find new files if one of these directories changes, which makes their modification times
+	/// a cheap way to tell whether an earlier result is still valid.
+	pub fn directories(&self) -> Result<Vec<PathBuf>, HepheastusError>
+	{
+		let mut globs: Vec<String> = vec!["**/".to_string()];
+		globs.extend(
+			self.globs
+				.iter()
+				.filter(|glob: &&String| glob.starts_with('!'))
+				.cloned(),
+		);
+		let glob_walker: GlobWalker = GlobWalkerBuilder::from_patterns(&self.base_directory, &globs)
+			.file_type(FileType::DIR)
 			.sort_by(FileSetGlob::cmp)
 			.build()?;
-		globe_walker
-			.into_iter()
-			.map(|dir_entry: Result<DirEntry, WalkError>| -> Result<PathBuf, WalkError> {
-				dir_entry.map(|dir_entry: DirEntry| -> PathBuf { dir_entry.into_path() })
-			})
-			.collect::<Result<Vec<PathBuf>, WalkError>>()
-			.map_err(HepheastusError::DirectoryWalkerError)
-			.map(|files: Vec<PathBuf>| -> FileSet { FileSet::new(name, files) })
+		let mut directories: Vec<PathBuf> = vec![self.base_directory.clone()];
+		for dir_entry in glob_walker
+		{
+			directories.push(dir_entry?.into_path());
+		}
+		Result::Ok(directories)
+	}
+
+	fn compile_globs(&self) -> Result<Override, HepheastusError>
+	{
+		let mut override_builder: OverrideBuilder = OverrideBuilder::new(&self.base_directory);
+		for glob in &self.globs
+		{
+			override_builder.add(glob)?;
+		}
+		Result::Ok(override_builder.build()?)
+	}
+
+	/// Whether the walk should yield `dir_entry` at all, or enter it if it is a directory, before any filters that need
+	/// to read the file.
+	fn is_walked(
+		&self,
+		dir_entry: &DirEntry,
+		device: Option<u64>,
+		git_ignore: Option<&mut GitIgnore>,
+	) -> bool
+	{
+		if !self.hidden && self.is_hidden(dir_entry.path())
+		{
+			return false;
+		}
+		if let Option::Some(device) = device
+			&& !dir_entry
+				.metadata()
+				.is_ok_and(|metadata: Metadata| metadata.dev() == device)
+		{
+			return false;
+		}
+		git_ignore.is_none_or(|git_ignore: &mut GitIgnore| {
+			!git_ignore.is_ignored(dir_entry.path(), dir_entry.file_type().is_dir())
+		})
+	}
+
+	/// Whether the path passes every filter; metadata is only read when there are attribute filters, and content only
+	/// when there are content filters and the attribute filters passed.
+	fn passes_filters(
+		&self,
+		path: &Path,
+		is_file: bool,
+		metadata: impl FnOnce() -> Result<Metadata, HepheastusError>,
+	) -> Result<bool, HepheastusError>
+	{
+		if !self.filters.is_empty()
+		{
+			let metadata: Metadata = metadata()?;
+			if !self.filters.iter().all(|filter: &FileFilter| filter.matches(&metadata))
+			{
+				return Result::Ok(false);
+			}
+		}
+		if !self.content_filters.is_empty() && (!is_file || !content_filter::matches_all(path, &self.content_filters)?)
+		{
+			return Result::Ok(false);
+		}
+		Result::Ok(true)
+	}
+
+	fn is_hidden(
+		&self,
+		path: &Path,
+	) -> bool
+	{
+		path.strip_prefix(&self.base_directory)
+			.unwrap_or(path)
+			.components()
+			.any(|component: Component| component.as_os_str().as_encoded_bytes().starts_with(b"."))
 	}
 
 	fn cmp(
@@ -53,3 +500,381 @@ impl FileSetGlob
 		lhs.path().cmp(rhs.path())
 	}
 }
+
+fn is_loop(error: &ignore::Error) -> bool
+{
+	match error
+	{
+		ignore::Error::Loop { .. } => true,
+		ignore::Error::WithPath { err, .. }
+		| ignore::Error::WithDepth { err, .. }
+		| ignore::Error::WithLineNumber { err, .. } => is_loop(err),
+		_ => false,
+	}
+}
+
+/// Whether `path` sorts after the first error of a parallel walk, so that it cannot change which error the walk fails
+/// with; every path below such a directory sorts after it as well.
+fn is_after(
+	first_error: &Mutex<Option<PathBuf>>,
+	path: &Path,
+) -> bool
+{
+	first_error
+		.lock()
+		.unwrap()
+		.as_ref()
+		.is_some_and(|first_error: &PathBuf| path > first_error.as_path())
+}
+
+/// A path found by a parallel walk, or an error together with the path it is about.
+type Found = Result<PathBuf, (PathBuf, HepheastusError)>;
+
+/// The path an error of the parallel walk is about, if it says.
+fn error_path(error: &ignore::Error) -> Option<&Path>
+{
+	match error
+	{
+		ignore::Error::WithPath { path, .. } => Option::Some(path),
+		ignore::Error::Loop { child, .. } => Option::Some(child),
+		ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => error_path(err),
+		_ => Option::None,
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ErrorPolicy
+{
+	/// Stop at the first path that cannot be read.
+	#[default]
+	Abort,
+	/// Note the error and carry on with the rest of the walk.
+	Collect,
+}
+
+/// The paths a [`FileSetGlob`] finds, produced one at a time while walking.
+///
+/// The walk is driven here rather than by `globwalker`, which cannot be told to skip a directory, so that a directory
+/// that is hidden, on another file system or ignored is not entered at all.
+pub struct FileSetIter<'glob>
+{
+	file_set_glob: &'glob FileSetGlob,
+	globs: Override,
+	walker: walkdir::IntoIter,
+	device: Option<u64>,
+	git_ignore: Option<GitIgnore>,
+	done: bool,
+}
+
+impl FileSetIter<'_>
+{
+	/// Decides about one entry of the walk, skipping the rest of a directory that does not need to be entered.
+	fn visit(
+		&mut self,
+		dir_entry: DirEntry,
+	) -> Result<Option<PathBuf>, HepheastusError>
+	{
+		let file_set_glob: &FileSetGlob = self.file_set_glob;
+		let path: &Path = dir_entry.path();
+		let relative_path: &Path = path.strip_prefix(&file_set_glob.base_directory).unwrap_or(path);
+		if relative_path.as_os_str().is_empty()
+		{
+			return Result::Ok(Option::None);
+		}
+		let is_dir: bool = dir_entry.file_type().is_dir();
+		let matched: Match<Glob> = self.globs.matched(relative_path, is_dir);
+		if matched.is_ignore() && is_dir
+		{
+			self.walker.skip_current_dir();
+			return Result::Ok(Option::None);
+		}
+		if !is_dir && !matched.is_whitelist()
+		{
+			return Result::Ok(Option::None);
+		}
+		if !file_set_glob.is_walked(&dir_entry, self.device, self.git_ignore.as_mut())
+		{
+			if is_dir
+			{
+				self.walker.skip_current_dir();
+			}
+			return Result::Ok(Option::None);
+		}
+		let file_type: std::fs::FileType = dir_entry.file_type();
+		let is_wanted: bool = match is_dir
+		{
+			true => file_set_glob.directories,
+			false => file_type.is_file() || file_type.is_symlink(),
+		};
+		if !matched.is_whitelist() || !is_wanted || dir_entry.depth() < file_set_glob.min_depth
+		{
+			return Result::Ok(Option::None);
+		}
+		let passes: bool =
+			file_set_glob.passes_filters(path, file_type.is_file(), || Result::Ok(dir_entry.metadata()?))?;
+		Result::Ok(passes.then(|| dir_entry.into_path()))
+	}
+}
+
+impl Iterator for FileSetIter<'_>
+{
+	type Item = Result<PathBuf, HepheastusError>;
+
+	fn next(&mut self) -> Option<Self::Item>
+	{
+		while !self.done
+		{
+			let path: Result<Option<PathBuf>, HepheastusError> = match self.walker.next()?
+			{
+				Result::Ok(dir_entry) => self.visit(dir_entry),
+				Result::Err(error) if error.loop_ancestor().is_some() => Result::Ok(Option::None),
+				Result::Err(error) => Result::Err(HepheastusError::DirectoryWalkerError(error)),
+			};
+			match path
+			{
+				Result::Ok(Option::Some(path)) => return Option::Some(Result::Ok(path)),
+				Result::Ok(Option::None) => (),
+				Result::Err(error) =>
+				{
+					self.done = self.file_set_glob.error_policy == ErrorPolicy::Abort;
+					return Option::Some(Result::Err(error));
+				}
+			}
+		}
+		Option::None
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::os::unix::net::UnixListener;
+	use std::path::Path;
+	use std::path::PathBuf;
+	use std::process::Command;
+	use std::process::ExitStatus;
+
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::io::file_set::FileSet;
+	use crate::hilcode::io::file_set_glob::ErrorPolicy;
+	use crate::hilcode::io::file_set_glob::FileSetGlob;
+	use crate::hilcode::test::env::TestEnv;
+
+	fn create(root_directory: &Path)
+	{
+		for file in ["a.rs", ".hidden.rs", "src/b.rs", "src/deep/c.rs", ".config/d.rs"]
+		{
+			let path: PathBuf = root_directory.join(file);
+			std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+			std::fs::write(path, "").unwrap();
+		}
+	}
+
+	fn search(file_set_glob: FileSetGlob) -> Vec<String>
+	{
+		let file_set: FileSet = file_set_glob.search("options").unwrap();
+		file_set.display_paths().collect()
+	}
+
+	#[test]
+	fn hidden_and_directories()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(root_directory);
+		let file_set_glob: FileSetGlob = FileSetGlob::new(root_directory, vec!["**/*".to_string()]);
+		assert_eq!(
+			vec![
+				".config",
+				".config/d.rs",
+				".hidden.rs",
+				"a.rs",
+				"src",
+				"src/b.rs",
+				"src/deep",
+				"src/deep/c.rs"
+			],
+			search(file_set_glob.clone())
+		);
+		assert_eq!(
+			vec!["a.rs", "src/b.rs", "src/deep/c.rs"],
+			search(file_set_glob.with_hidden(false).with_directories(false))
+		);
+	}
+
+	#[test]
+	fn depth()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(root_directory);
+		let file_set_glob: FileSetGlob = FileSetGlob::new(root_directory, vec!["**/*.rs".to_string()]);
+		assert_eq!(vec![".hidden.rs", "a.rs"], search(file_set_glob.clone().with_depth(1, 1)));
+		assert_eq!(
+			vec![".config/d.rs", "src/b.rs", "src/deep/c.rs"],
+			search(file_set_glob.clone().with_depth(2, usize::MAX))
+		);
+		assert_eq!(5, search(file_set_glob.with_one_file_system(true)).len());
+	}
+
+	#[test]
+	fn follow_links_skips_loops()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(root_directory);
+		std::os::unix::fs::symlink("src", root_directory.join("link")).unwrap();
+		std::os::unix::fs::symlink("..", root_directory.join("src/deep/up")).unwrap();
+		let file_set_glob: FileSetGlob =
+			FileSetGlob::new(root_directory, vec!["**/*.rs".to_string(), "!.*/**".to_string(), "!.*".to_string()]);
+		assert_eq!(vec!["a.rs", "src/b.rs", "src/deep/c.rs"], search(file_set_glob.clone()));
+		assert_eq!(
+			vec!["a.rs", "link/b.rs", "link/deep/c.rs", "src/b.rs", "src/deep/c.rs"],
+			search(file_set_glob.with_follow_links(true))
+		);
+	}
+
+	#[test]
+	fn error_policy()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(root_directory);
+		std::os::unix::fs::symlink("missing", root_directory.join("dangling")).unwrap();
+		let file_set_glob: FileSetGlob = FileSetGlob::new(root_directory, vec!["*".to_string(), "!.*".to_string()])
+			.with_follow_links(true)
+			.with_depth(1, 1);
+		assert!(file_set_glob.search("abort").is_err());
+		let paths: Vec<Result<PathBuf, HepheastusError>> = file_set_glob.iter().unwrap().collect();
+		assert_eq!(2, paths.len());
+		assert!(paths[0].is_ok() && paths[1].is_err());
+		let file_set_glob: FileSetGlob = file_set_glob.with_error_policy(ErrorPolicy::Collect);
+		let (file_set, errors) = file_set_glob.search_with_errors("collect").unwrap();
+		assert_eq!(
+			vec![root_directory.join("a.rs"), root_directory.join("src")],
+			file_set.absolute_paths().collect::<Vec<PathBuf>>()
+		);
+		assert_eq!(1, errors.len());
+		assert_eq!(3, file_set_glob.iter().unwrap().count());
+	}
+
+	#[test]
+	fn ignored_directories_are_not_entered()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(root_directory);
+		std::fs::create_dir_all(root_directory.join(".git")).unwrap();
+		std::fs::create_dir_all(root_directory.join("target/debug")).unwrap();
+		std::os::unix::fs::symlink("missing", root_directory.join("target/debug/dangling")).unwrap();
+		std::fs::write(root_directory.join(".gitignore"), "target/\n").unwrap();
+		let file_set_glob: FileSetGlob =
+			FileSetGlob::new(root_directory, vec!["**/*".to_string()]).with_follow_links(true);
+		assert!(file_set_glob.search("everything").is_err());
+		let file_set_glob: FileSetGlob = file_set_glob.with_git_ignore(true).with_hidden(false);
+		assert_eq!(vec!["a.rs", "src", "src/b.rs", "src/deep", "src/deep/c.rs"], search(file_set_glob.clone()));
+		assert_eq!(vec!["a.rs", "src", "src/b.rs", "src/deep", "src/deep/c.rs"], search(file_set_glob.with_threads(4)));
+	}
+
+	#[test]
+	fn parallel_search_matches_sequential_search()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(root_directory);
+		for index in 0..50
+		{
+			let path: PathBuf = root_directory.join(format!("many/{}/{}.rs", index % 7, index));
+			std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+			std::fs::write(path, "").unwrap();
+		}
+		std::fs::create_dir_all(root_directory.join(".git/info")).unwrap();
+		std::fs::write(root_directory.join(".git/info/exclude"), "many/2/\n").unwrap();
+		std::fs::write(root_directory.join(".gitignore"), "many/3/\n*5.rs\n").unwrap();
+		std::fs::write(root_directory.join("many/4/.ignore"), "*.rs\n!4.rs\n").unwrap();
+		std::fs::write(root_directory.join("many/5/.gitignore"), "!*5.rs\n").unwrap();
+		let file_set_globs: Vec<FileSetGlob> = vec![
+			FileSetGlob::new(root_directory, vec!["**/*".to_string()]),
+			FileSetGlob::new(root_directory, vec!["**/*.rs".to_string(), "!many/1/**".to_string()]),
+			FileSetGlob::new(root_directory, vec!["**/*".to_string()]).with_git_ignore(true),
+			FileSetGlob::new(root_directory.join("many"), vec!["**/*".to_string()]).with_git_ignore(true),
+			FileSetGlob::new(root_directory, vec!["**/*".to_string()])
+				.with_hidden(false)
+				.with_directories(false)
+				.with_depth(2, 3),
+		];
+		for file_set_glob in file_set_globs
+		{
+			assert_eq!(
+				file_set_glob
+					.search("sequential")
+					.unwrap()
+					.iter()
+					.collect::<Vec<&PathBuf>>(),
+				file_set_glob
+					.clone()
+					.with_threads(4)
+					.search("sequential")
+					.unwrap()
+					.iter()
+					.collect::<Vec<&PathBuf>>(),
+				"{:?}",
+				file_set_glob
+			);
+		}
+	}
+
+	#[test]
+	fn parallel_search_leaves_out_special_files()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(root_directory);
+		let status: ExitStatus = Command::new("mkfifo")
+			.arg(root_directory.join("src/fifo"))
+			.status()
+			.unwrap();
+		assert!(status.success());
+		let _listener: UnixListener = UnixListener::bind(root_directory.join("src/socket")).unwrap();
+		for file_set_glob in [
+			FileSetGlob::new(root_directory, vec!["**/*".to_string()]),
+			FileSetGlob::new(root_directory, vec!["**/*".to_string()]).with_directories(false),
+		]
+		{
+			let sequential: Vec<String> = search(file_set_glob.clone());
+			assert!(
+				!sequential
+					.iter()
+					.any(|path: &String| path == "src/fifo" || path == "src/socket")
+			);
+			assert_eq!(sequential, search(file_set_glob.with_threads(4)));
+		}
+	}
+
+	#[test]
+	fn parallel_search_aborts_on_the_first_error()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		for index in 0..20
+		{
+			std::os::unix::fs::symlink("missing", root_directory.join(format!("dangling{:02}", index))).unwrap();
+		}
+		let file_set_glob: FileSetGlob =
+			FileSetGlob::new(root_directory, vec!["*".to_string()]).with_follow_links(true);
+		let sequential: String = file_set_glob.search("sequential").unwrap_err().to_string();
+		assert!(sequential.contains("dangling00"), "{}", sequential);
+		for _ in 0..10
+		{
+			let parallel: String = file_set_glob
+				.clone()
+				.with_threads(4)
+				.search("parallel")
+				.unwrap_err()
+				.to_string();
+			assert!(parallel.contains("dangling00"), "{}", parallel);
+		}
+	}
+}
@@ -0,0 +1,247 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+use crate::hilcode::io::file_list::FileListSource;
+use crate::hilcode::io::file_set::FileSet;
+use crate::hilcode::io::file_set_expression::FileSetExpression;
+use crate::hilcode::io::file_set_glob::FileSetGlob;
+
+/// How a named file set gets its files.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileSetDefinition
+{
+	Files(FileSet),
+	Glob(FileSetGlob),
+	List(FileListSource),
+	Expression(FileSetExpression),
+}
+
+/// Named file set definitions that may refer to each other.
+///
+/// A set is only resolved when asked for, and then at most once: the result, including the searches behind it, is
+/// remembered until a definition changes. Two registries are equal when their definitions are, whatever each has
+/// resolved so far.
+#[derive(Debug, Default)]
+pub struct FileSetRegistry
+{
+	definitions: BTreeMap<String, FileSetDefinition>,
+	resolved: RefCell<BTreeMap<String, FileSet>>,
+}
+
+impl FileSetRegistry
+{
+	pub fn define(
+		&mut self,
+		name: impl Into<String>,
+		definition: FileSetDefinition,
+	)
+	{
+		self.definitions.insert(name.into(), definition);
+		self.resolved.get_mut().clear();
+	}
+
+	pub fn get(
+		&self,
+		name: &str,
+	) -> Option<&FileSetDefinition>
+	{
+		self.definitions.get(name)
+	}
+
+	pub fn names(&self) -> impl Iterator<Item = &str>
+	{
+		self.definitions.keys().map(String::as_str)
+	}
+
+	pub fn resolve(
+		&self,
+		name: &str,
+	) -> Result<FileSet, HepheastusError>
+	{
+		self.resolve_with(name, &mut Vec::new())
+	}
+
+	/// Resolves `name`; `stack` holds the sets being resolved that led here, to detect a set that contains itself.
+	fn resolve_with(
+		&self,
+		name: &str,
+		stack: &mut Vec<String>,
+	) -> Result<FileSet, HepheastusError>
+	{
+		if let Option::Some(file_set) = self.resolved.borrow().get(name)
+		{
+			return Result::Ok(file_set.clone());
+		}
+		if let Option::Some(start) = stack.iter().position(|entry: &String| entry == name)
+		{
+			let mut cycle: Vec<String> = stack[start..].to_vec();
+			cycle.push(name.to_string());
+			return Result::Err(HepheastusError::FileSetCycle(cycle));
+		}
+		let definition: &FileSetDefinition = self
+			.definitions
+			.get(name)
+			.ok_or_else(|| HepheastusError::UnknownFileSet(name.to_string()))?;
+		stack.push(name.to_string());
+		let file_set: Result<FileSet, HepheastusError> = match definition
+		{
+			FileSetDefinition::Files(file_set) => Result::Ok(file_set.renamed(name)),
+			FileSetDefinition::Glob(file_set_glob) => file_set_glob.search(name),
+			FileSetDefinition::List(file_list_source) => file_list_source.read(name),
+			FileSetDefinition::Expression(expression) =>
+			{
+				self.evaluate(expression, stack)
+					.map(|file_set: FileSet| file_set.renamed(name))
+			}
+		};
+		stack.pop();
+		let file_set: FileSet = file_set?;
+		self.resolved.borrow_mut().insert(name.to_string(), file_set.clone());
+		Result::Ok(file_set)
+	}
+
+	fn evaluate(
+		&self,
+		expression: &FileSetExpression,
+		stack: &mut Vec<String>,
+	) -> Result<FileSet, HepheastusError>
+	{
+		match expression
+		{
+			FileSetExpression::Reference(name) => self.resolve_with(name, stack),
+			FileSetExpression::Union(lhs, rhs) =>
+			{
+				let (lhs, rhs) = self.evaluate_operands(lhs, rhs, stack)?;
+				Result::Ok(lhs.union(lhs.name(), &rhs))
+			}
+			FileSetExpression::Intersection(lhs, rhs) =>
+			{
+				let (lhs, rhs) = self.evaluate_operands(lhs, rhs, stack)?;
+				Result::Ok(lhs.intersection(lhs.name(), &rhs))
+			}
+			FileSetExpression::Difference(lhs, rhs) =>
+			{
+				let (lhs, rhs) = self.evaluate_operands(lhs, rhs, stack)?;
+				Result::Ok(lhs.difference(lhs.name(), &rhs))
+			}
+			FileSetExpression::SymmetricDifference(lhs, rhs) =>
+			{
+				let (lhs, rhs) = self.evaluate_operands(lhs, rhs, stack)?;
+				Result::Ok(lhs.symmetric_difference(lhs.name(), &rhs))
+			}
+		}
+	}
+
+	fn evaluate_operands(
+		&self,
+		lhs: &FileSetExpression,
+		rhs: &FileSetExpression,
+		stack: &mut Vec<String>,
+	) -> Result<(FileSet, FileSet), HepheastusError>
+	{
+		Result::Ok((self.evaluate(lhs, stack)?, self.evaluate(rhs, stack)?))
+	}
+}
+
+impl PartialEq for FileSetRegistry
+{
+	fn eq(
+		&self,
+		other: &Self,
+	) -> bool
+	{
+		self.definitions == other.definitions
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::io::file_set::FileSet;
+	use crate::hilcode::io::file_set_expression::FileSetExpression;
+	use crate::hilcode::io::file_set_glob::FileSetGlob;
+	use crate::hilcode::io::file_set_registry::FileSetDefinition;
+	use crate::hilcode::io::file_set_registry::FileSetRegistry;
+	use crate::hilcode::test::env::TestEnv;
+
+	fn expression(text: &str) -> FileSetDefinition
+	{
+		FileSetDefinition::Expression(FileSetExpression::parse(Path::new("hephaestus.build"), 1, text).unwrap())
+	}
+
+	#[test]
+	fn references()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::create_dir(root_directory.join("tests")).unwrap();
+		for file in ["main.rs", "lib.rs", "tests/cli.rs"]
+		{
+			std::fs::write(root_directory.join(file), "").unwrap();
+		}
+		let mut file_set_registry: FileSetRegistry = FileSetRegistry::default();
+		file_set_registry.define(
+			"rust-sources",
+			FileSetDefinition::Glob(FileSetGlob::new(root_directory, vec!["**/*.rs".to_string()])),
+		);
+		file_set_registry
+			.define("tests", FileSetDefinition::Glob(FileSetGlob::new(root_directory, vec!["tests/**".to_string()])));
+		file_set_registry.define("sources", expression("@rust-sources - @tests"));
+		let sources: FileSet = file_set_registry.resolve("sources").unwrap();
+		assert_eq!("sources", sources.name());
+		assert_eq!(
+			vec![root_directory.join("lib.rs"), root_directory.join("main.rs")],
+			sources.absolute_paths().collect::<Vec<PathBuf>>()
+		);
+		std::fs::write(root_directory.join("build.rs"), "").unwrap();
+		assert_eq!(sources, file_set_registry.resolve("sources").unwrap());
+		file_set_registry
+			.define("build", FileSetDefinition::Files(FileSet::new("", root_directory, vec![PathBuf::from("x")])));
+		assert_eq!(3, file_set_registry.resolve("sources").unwrap().len());
+	}
+
+	#[test]
+	fn equality_ignores_what_was_resolved()
+	{
+		let mut lhs: FileSetRegistry = FileSetRegistry::default();
+		lhs.define("a", FileSetDefinition::Files(FileSet::new("", Path::new("/"), vec![PathBuf::from("x")])));
+		let mut rhs: FileSetRegistry = FileSetRegistry::default();
+		rhs.define("a", FileSetDefinition::Files(FileSet::new("", Path::new("/"), vec![PathBuf::from("x")])));
+		lhs.resolve("a").unwrap();
+		assert_eq!(lhs, rhs);
+		rhs.define("b", expression("@a"));
+		assert_ne!(lhs, rhs);
+	}
+
+	#[test]
+	fn cycle()
+	{
+		let mut file_set_registry: FileSetRegistry = FileSetRegistry::default();
+		file_set_registry.define("a", expression("@b + @c"));
+		file_set_registry.define("b", expression("@c"));
+		file_set_registry.define("c", expression("@a"));
+		match file_set_registry.resolve("a")
+		{
+			Result::Err(HepheastusError::FileSetCycle(cycle)) => assert_eq!(vec!["a", "b", "c", "a"], cycle),
+			result => panic!("expected a cycle, got {:?}", result),
+		}
+	}
+
+	#[test]
+	fn unknown()
+	{
+		let mut file_set_registry: FileSetRegistry = FileSetRegistry::default();
+		file_set_registry.define("a", expression("@b"));
+		assert!(matches!(
+			file_set_registry.resolve("a"),
+			Result::Err(HepheastusError::UnknownFileSet(name)) if name == "b"
+		));
+	}
+}
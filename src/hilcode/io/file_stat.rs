@@ -0,0 +1,224 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::fs::Metadata;
+use std::io::BufReader;
+use std::io::Error;
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use time::OffsetDateTime;
+use time::macros::format_description;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hash(u64);
+
+impl Display for Hash
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		formatter.write_fmt(format_args!("{:16x}", self.0))
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileSize(u64);
+
+impl FileSize
+{
+	pub fn new(bytes: u64) -> FileSize
+	{
+		FileSize(bytes)
+	}
+
+	pub fn of(metadata: &Metadata) -> FileSize
+	{
+		FileSize(metadata.size())
+	}
+
+	pub fn bytes(&self) -> u64
+	{
+		self.0
+	}
+}
+
+/// Parses sizes such as `512`, `4k`, `10MB` or `1MiB`: `k`, `M` and `G` count in powers of 1000, `Ki`, `Mi` and `Gi`
+/// in powers of 1024, and a trailing `B` is optional.
+impl FromStr for FileSize
+{
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err>
+	{
+		let text: &str = text.trim();
+		let split: usize = text
+			.find(|character: char| !character.is_ascii_digit())
+			.unwrap_or(text.len());
+		let (number, unit) = text.split_at(split);
+		let number: u64 = number
+			.parse()
+			.map_err(|_| format!("expected a size like '1MiB', not '{}'", text))?;
+		let unit: &str = unit.trim();
+		let multiplier: u64 = match unit.strip_suffix('B').unwrap_or(unit)
+		{
+			"" => 1,
+			"k" | "K" => 1000,
+			"Ki" => 1 << 10,
+			"M" => 1000 * 1000,
+			"Mi" => 1 << 20,
+			"G" => 1000 * 1000 * 1000,
+			"Gi" => 1 << 30,
+			_ => return Result::Err(format!("unknown size unit in '{}'", text)),
+		};
+		number
+			.checked_mul(multiplier)
+			.map(FileSize)
+			.ok_or_else(|| format!("size '{}' is too large", text))
+	}
+}
+
+impl Display for FileSize
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		formatter.write_fmt(format_args!("{}B", self.0))
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileMode(u32);
+
+impl FileMode
+{
+	pub fn new(bits: u32) -> FileMode
+	{
+		FileMode(bits)
+	}
+
+	pub fn of(metadata: &Metadata) -> FileMode
+	{
+		FileMode(metadata.mode())
+	}
+
+	pub fn bits(&self) -> u32
+	{
+		self.0
+	}
+}
+
+impl Display for FileMode
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		let permissions: [u8; 9] = [
+			if self.0 & 0o400 == 0 { b'-' } else { b'r' },
+			if self.0 & 0o200 == 0 { b'-' } else { b'w' },
+			if self.0 & 0o100 == 0 { b'-' } else { b'x' },
+			if self.0 & 0o040 == 0 { b'-' } else { b'r' },
+			if self.0 & 0o020 == 0 { b'-' } else { b'w' },
+			if self.0 & 0o010 == 0 { b'-' } else { b'x' },
+			if self.0 & 0o004 == 0 { b'-' } else { b'r' },
+			if self.0 & 0o002 == 0 { b'-' } else { b'w' },
+			if self.0 & 0o001 == 0 { b'-' } else { b'x' },
+		];
+		let permissions: &str = unsafe { std::str::from_utf8_unchecked(&permissions) };
+		formatter.write_fmt(format_args!("{}", permissions))
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileStat
+{
+	modified: SystemTime,
+	file_size: FileSize,
+	file_mode: FileMode,
+	hash: Hash,
+}
+
+impl Display for FileStat
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		let modified: OffsetDateTime = self.modified.into();
+		let format = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]");
+		let modified = modified.format(format).unwrap();
+		formatter.write_fmt(format_args!("{}|{}|{}|{}", &modified, &self.file_size, &self.file_mode, &self.hash))
+	}
+}
+
+impl FileStat
+{
+	pub fn modified(&self) -> SystemTime
+	{
+		self.modified
+	}
+
+	pub fn file_size(&self) -> &FileSize
+	{
+		&self.file_size
+	}
+
+	pub fn file_mode(&self) -> &FileMode
+	{
+		&self.file_mode
+	}
+
+	pub fn hash(&self) -> &Hash
+	{
+		&self.hash
+	}
+
+	/// Whether `metadata` still describes the file this was taken from, so its hash need not be recomputed.
+	pub fn is_current(
+		&self,
+		metadata: &Metadata,
+	) -> bool
+	{
+		metadata
+			.modified()
+			.is_ok_and(|modified: SystemTime| modified == self.modified)
+			&& metadata.size() == self.file_size.0
+			&& metadata.mode() == self.file_mode.0
+	}
+
+	pub fn get(path_buf: &PathBuf) -> Result<FileStat, HepheastusError>
+	{
+		let metadata: Metadata = path_buf.metadata()?;
+		let modified: SystemTime = metadata.modified()?;
+		let file_size: FileSize = FileSize::of(&metadata);
+		let file_mode: FileMode = FileMode::of(&metadata);
+		let hash: Hash = FileStat::get_hash(path_buf)?;
+		let file_stat: FileStat = FileStat {
+			modified,
+			file_size,
+			file_mode,
+			hash,
+		};
+		Result::Ok(file_stat)
+	}
+
+	fn get_hash(path_buf: &PathBuf) -> Result<Hash, Error>
+	{
+		let file: File = File::open(path_buf)?;
+		let mut buf_reader: BufReader<File> = BufReader::new(file);
+		let mut hasher: Xxh3 = Xxh3::new();
+		std::io::copy(&mut buf_reader, &mut hasher)?;
+		Result::Ok(Hash(hasher.digest()))
+	}
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ignore::Match;
+use ignore::gitignore::Gitignore;
+use ignore::gitignore::GitignoreBuilder;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+/// Ignore files in the order they are consulted within one directory; `.ignore` overrides `.gitignore`.
+const IGNORE_FILE_NAMES: [&str; 2] = [".ignore", ".gitignore"];
+
+const GIT_DIRECTORY_NAME: &str = ".git";
+
+/// Decides which paths git would ignore below `base_directory`.
+///
+/// Patterns come from `.gitignore` and `.ignore` files in every directory from the repository root down, where a
+/// deeper file overrides a shallower one, then from `.git/info/exclude` and finally from the file named by git's
+/// `core.excludesFile`. As in git, nothing inside an ignored directory can be re-included, and `.git` itself is
+/// always ignored. Like git, patterns that do not compile are skipped.
+pub struct GitIgnore
+{
+	base_directory: PathBuf,
+	absolute_base_directory: PathBuf,
+	top_directory: PathBuf,
+	exclude: Gitignore,
+	global: Gitignore,
+	directory_matchers: HashMap<PathBuf, Vec<Gitignore>>,
+	ignored_directories: HashMap<PathBuf, bool>,
+}
+
+impl GitIgnore
+{
+	pub fn new(base_directory: &Path) -> Result<GitIgnore, HepheastusError>
+	{
+		let absolute_base_directory: PathBuf = base_directory.canonicalize()?;
+		let repository_root: Option<PathBuf> = absolute_base_directory
+			.ancestors()
+			.find(|directory: &&Path| directory.join(GIT_DIRECTORY_NAME).exists())
+			.map(Path::to_path_buf);
+		let top_directory: PathBuf = repository_root
+			.clone()
+			.unwrap_or_else(|| absolute_base_directory.clone());
+		let (exclude, global) = match repository_root
+		{
+			Option::Some(repository_root) =>
+			{
+				let exclude_path: PathBuf = repository_root.join(GIT_DIRECTORY_NAME).join("info").join("exclude");
+				let mut builder: GitignoreBuilder = GitignoreBuilder::new(&repository_root);
+				let _ = builder.add(exclude_path);
+				let exclude: Gitignore = builder.build().unwrap_or_else(|_| Gitignore::empty());
+				let (global, _) = GitignoreBuilder::new(&repository_root).build_global();
+				(exclude, global)
+			}
+			Option::None => (Gitignore::empty(), Gitignore::empty()),
+		};
+		Result::Ok(GitIgnore {
+			base_directory: base_directory.to_path_buf(),
+			absolute_base_directory,
+			top_directory,
+			exclude,
+			global,
+			directory_matchers: HashMap::new(),
+			ignored_directories: HashMap::new(),
+		})
+	}
+
+	/// Whether git ignores `path`, which is expected to be `base_directory` joined with a relative path.
+	pub fn is_ignored(
+		&mut self,
+		path: &Path,
+		is_dir: bool,
+	) -> bool
+	{
+		let Result::Ok(relative_path) = path.strip_prefix(&self.base_directory)
+		else
+		{
+			return false;
+		};
+		let path: PathBuf = self.absolute_base_directory.join(relative_path);
+		match path.parent()
+		{
+			Option::Some(parent) if self.is_ignored_directory(parent) => true,
+			_ => self.is_matched(&path, is_dir),
+		}
+	}
+
+	fn is_ignored_directory(
+		&mut self,
+		directory: &Path,
+	) -> bool
+	{
+		if directory == self.top_directory || !directory.starts_with(&self.top_directory)
+		{
+			return false;
+		}
+		if let Option::Some(ignored) = self.ignored_directories.get(directory)
+		{
+			return *ignored;
+		}
+		let ignored: bool = match directory.parent()
+		{
+			Option::Some(parent) if self.is_ignored_directory(parent) => true,
+			_ => self.is_matched(directory, true),
+		};
+		self.ignored_directories.insert(directory.to_path_buf(), ignored);
+		ignored
+	}
+
+	fn is_matched(
+		&mut self,
+		path: &Path,
+		is_dir: bool,
+	) -> bool
+	{
+		if path
+			.file_name()
+			.is_some_and(|file_name: &OsStr| file_name == GIT_DIRECTORY_NAME)
+		{
+			return true;
+		}
+		let directories: Vec<PathBuf> = path
+			.ancestors()
+			.skip(1)
+			.take_while(|directory: &&Path| directory.starts_with(&self.top_directory))
+			.map(Path::to_path_buf)
+			.collect();
+		for directory in directories
+		{
+			for matcher in self.matchers(&directory)
+			{
+				match matcher.matched(path, is_dir)
+				{
+					Match::Ignore(_) => return true,
+					Match::Whitelist(_) => return false,
+					Match::None =>
+					{}
+				}
+			}
+		}
+		[&self.exclude, &self.global]
+			.iter()
+			.find_map(|matcher: &&Gitignore| {
+				match matcher.matched(path, is_dir)
+				{
+					Match::Ignore(_) => Option::Some(true),
+					Match::Whitelist(_) => Option::Some(false),
+					Match::None => Option::None,
+				}
+			})
+			.unwrap_or(false)
+	}
+
+	fn matchers(
+		&mut self,
+		directory: &Path,
+	) -> &[Gitignore]
+	{
+		self.directory_matchers
+			.entry(directory.to_path_buf())
+			.or_insert_with(|| {
+				IGNORE_FILE_NAMES
+					.iter()
+					.map(|file_name: &&str| directory.join(file_name))
+					.filter(|path: &PathBuf| path.is_file())
+					.map(|path: PathBuf| Gitignore::new(path).0)
+					.collect()
+			})
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::io::file_set::FileSet;
+	use crate::hilcode::io::file_set_glob::FileSetGlob;
+	use crate::hilcode::test::env::TestEnv;
+
+	fn create(
+		root_directory: &Path,
+		files: &[(&str, &str)],
+	)
+	{
+		for (file, content) in files
+		{
+			let path: PathBuf = root_directory.join(file);
+			std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+			std::fs::write(path, content).unwrap();
+		}
+	}
+
+	fn search(
+		root_directory: &Path,
+		respect_git_ignore: bool,
+	) -> Vec<String>
+	{
+		let file_set: FileSet = FileSetGlob::new(root_directory, vec!["**/*".to_string()])
+			.with_git_ignore(respect_git_ignore)
+			.search("files")
+			.unwrap();
+		file_set
+			.iter()
+			.filter(|path: &&PathBuf| file_set.absolute(path).is_file())
+			.map(|path: &PathBuf| path.to_string_lossy().into_owned())
+			.collect()
+	}
+
+	#[test]
+	fn git_ignore()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(
+			root_directory,
+			&[
+				(".git/HEAD", "ref: refs/heads/main\n"),
+				(".git/info/exclude", "*.swp\n"),
+				(".gitignore", "*.hephaestus-log\n!keep.hephaestus-log\nout/\n"),
+				("keep.hephaestus-log", ""),
+				("drop.hephaestus-log", ""),
+				("main.rs.swp", ""),
+				("out/app", ""),
+				("src/out", ""),
+				("src/.gitignore", "generated.rs\n"),
+				("src/generated.rs", ""),
+				("src/main.rs", ""),
+				("src/tools/.ignore", "!generated.rs\n"),
+				("src/tools/generated.rs", ""),
+			],
+		);
+		let expected: Vec<&str> = vec![
+			".gitignore",
+			"keep.hephaestus-log",
+			"src/.gitignore",
+			"src/main.rs",
+			"src/out",
+			"src/tools/.ignore",
+			"src/tools/generated.rs",
+		];
+		assert_eq!(expected, search(root_directory, true));
+		assert_eq!(13, search(root_directory, false).len());
+	}
+
+	#[test]
+	fn ignored_directory_cannot_be_re_included()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		create(
+			root_directory,
+			&[
+				(".git/HEAD", ""),
+				(".gitignore", "vendor/\n!vendor/keep.rs\n"),
+				("vendor/keep.rs", ""),
+				("lib.rs", ""),
+			],
+		);
+		assert_eq!(vec![".gitignore", "lib.rs"], search(root_directory, true));
+	}
+}
@@ -0,0 +1,386 @@
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+const GIT_DIRECTORY_NAME: &str = ".git";
+const INDEX_FILE_NAME: &str = "index";
+const SIGNATURE: &[u8; 4] = b"DIRC";
+const HEADER_LENGTH: usize = 12;
+/// The stat data, mode, object name and flags in front of each path; object names are SHA-1 hashes.
+const ENTRY_HEADER_LENGTH: usize = 62;
+const MODE_OFFSET: usize = 24;
+const FLAGS_OFFSET: usize = 60;
+const EXTENDED_FLAG: u16 = 0x4000;
+const STAGE_MASK: u16 = 0x3000;
+const NAME_LENGTH_MASK: u16 = 0x0fff;
+const FILE_TYPE_MASK: u32 = 0o170000;
+const DIRECTORY_TYPE: u32 = 0o040000;
+
+/// A path recorded in the git index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GitIndexEntry
+{
+	path: PathBuf,
+	mode: u32,
+	stage: u8,
+}
+
+impl GitIndexEntry
+{
+	/// The path relative to the top of the repository.
+	pub fn path(&self) -> &Path
+	{
+		&self.path
+	}
+
+	pub fn mode(&self) -> u32
+	{
+		self.mode
+	}
+
+	/// The merge stage: 0 normally, 1 to 3 for the sides of an unresolved conflict.
+	pub fn stage(&self) -> u8
+	{
+		self.stage
+	}
+}
+
+/// The files git tracks, read straight from `.git/index` so that no git binary is needed.
+///
+/// Index versions 2, 3 and 4 are understood. Extensions such as the cache tree are skipped, and so are the directory
+/// entries of a sparse index, so the paths are those `git ls-files` would list.
+#[derive(Debug, PartialEq)]
+pub struct GitIndex
+{
+	top_directory: PathBuf,
+	entries: Vec<GitIndexEntry>,
+}
+
+impl GitIndex
+{
+	/// Reads the index of the repository containing `directory`.
+	pub fn load(directory: &Path) -> Result<GitIndex, HepheastusError>
+	{
+		let directory: PathBuf = directory.canonicalize()?;
+		let top_directory: &Path = directory
+			.ancestors()
+			.find(|directory: &&Path| directory.join(GIT_DIRECTORY_NAME).exists())
+			.ok_or_else(|| {
+				HepheastusError::GitIndexError(format!("{} is not inside a git repository", directory.display()))
+			})?;
+		let index_path: PathBuf = git_directory(top_directory)?.join(INDEX_FILE_NAME);
+		let content: Vec<u8> = std::fs::read(&index_path)?;
+		let entries: Vec<GitIndexEntry> = GitIndex::parse(&content).map_err(|message: String| {
+			HepheastusError::GitIndexError(format!("{}: {}", index_path.display(), message))
+		})?;
+		Result::Ok(GitIndex {
+			top_directory: top_directory.to_path_buf(),
+			entries,
+		})
+	}
+
+	/// The entries in `content`, the bytes of an index file.
+	pub fn parse(content: &[u8]) -> Result<Vec<GitIndexEntry>, String>
+	{
+		if content.len() < HEADER_LENGTH || &content[..4] != SIGNATURE
+		{
+			return Result::Err("not a git index".to_string());
+		}
+		let version: u32 = read_u32(content, 4)?;
+		if !(2..=4).contains(&version)
+		{
+			return Result::Err(format!("unsupported index version {}", version));
+		}
+		let count: u32 = read_u32(content, 8)?;
+		let mut entries: Vec<GitIndexEntry> = Vec::with_capacity(count as usize);
+		let mut offset: usize = HEADER_LENGTH;
+		let mut previous_path: Vec<u8> = Vec::new();
+		for _ in 0..count
+		{
+			let start: usize = offset;
+			let mode: u32 = read_u32(content, start + MODE_OFFSET)?;
+			let flags: u16 = read_u16(content, start + FLAGS_OFFSET)?;
+			offset = start + ENTRY_HEADER_LENGTH;
+			if flags & EXTENDED_FLAG != 0
+			{
+				if version < 3
+				{
+					return Result::Err("extended flags in a version 2 index".to_string());
+				}
+				offset += 2;
+			}
+			let path: Vec<u8> = if version == 4
+			{
+				let (removed, length) = read_varint(content, offset)?;
+				offset += length;
+				let kept: usize = previous_path
+					.len()
+					.checked_sub(removed)
+					.ok_or_else(|| format!("bad path compression at byte {}", start))?;
+				let suffix: &[u8] = read_name(content, offset)?;
+				offset += suffix.len() + 1;
+				[&previous_path[..kept], suffix].concat()
+			}
+			else
+			{
+				let name: &[u8] = read_name(content, offset)?;
+				if (flags & NAME_LENGTH_MASK) != NAME_LENGTH_MASK && name.len() != (flags & NAME_LENGTH_MASK) as usize
+				{
+					return Result::Err(format!("bad name length at byte {}", start));
+				}
+				// Entries are padded with one to eight NUL bytes to a multiple of eight bytes.
+				offset = start + (offset - start + name.len() + 8) / 8 * 8;
+				name.to_vec()
+			};
+			if mode & FILE_TYPE_MASK != DIRECTORY_TYPE
+			{
+				entries.push(GitIndexEntry {
+					path: PathBuf::from(OsStr::from_bytes(&path)),
+					mode,
+					stage: ((flags & STAGE_MASK) >> 12) as u8,
+				});
+			}
+			previous_path = path;
+		}
+		Result::Ok(entries)
+	}
+
+	pub fn top_directory(&self) -> &Path
+	{
+		&self.top_directory
+	}
+
+	pub fn entries(&self) -> &[GitIndexEntry]
+	{
+		&self.entries
+	}
+
+	/// The tracked paths below `directory`, relative to it, each listed once even while it is in conflict.
+	pub fn files_below(
+		&self,
+		directory: &Path,
+	) -> Result<Vec<PathBuf>, HepheastusError>
+	{
+		let directory: PathBuf = directory.canonicalize()?;
+		let prefix: &Path = directory.strip_prefix(&self.top_directory).map_err(|_| {
+			HepheastusError::GitIndexError(format!(
+				"{} is not inside {}",
+				directory.display(),
+				self.top_directory.display()
+			))
+		})?;
+		let mut files: Vec<PathBuf> = self
+			.entries
+			.iter()
+			.filter_map(|entry: &GitIndexEntry| entry.path.strip_prefix(prefix).ok())
+			.map(Path::to_path_buf)
+			.collect();
+		files.dedup();
+		Result::Ok(files)
+	}
+}
+
+/// The git directory of the repository at `top_directory`, following the `gitdir:` file of a worktree or submodule.
+fn git_directory(top_directory: &Path) -> Result<PathBuf, HepheastusError>
+{
+	let path: PathBuf = top_directory.join(GIT_DIRECTORY_NAME);
+	if path.is_dir()
+	{
+		return Result::Ok(path);
+	}
+	let content: String = std::fs::read_to_string(&path)?;
+	let git_directory: &str = content
+		.trim()
+		.strip_prefix("gitdir:")
+		.ok_or_else(|| HepheastusError::GitIndexError(format!("{} does not name a git directory", path.display())))?;
+	Result::Ok(top_directory.join(git_directory.trim()))
+}
+
+fn read_u32(
+	content: &[u8],
+	offset: usize,
+) -> Result<u32, String>
+{
+	content
+		.get(offset..offset + 4)
+		.map(|bytes: &[u8]| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+		.ok_or_else(|| format!("truncated at byte {}", offset))
+}
+
+fn read_u16(
+	content: &[u8],
+	offset: usize,
+) -> Result<u16, String>
+{
+	content
+		.get(offset..offset + 2)
+		.map(|bytes: &[u8]| u16::from_be_bytes([bytes[0], bytes[1]]))
+		.ok_or_else(|| format!("truncated at byte {}", offset))
+}
+
+/// The NUL-terminated name starting at `offset`, without the NUL.
+fn read_name(
+	content: &[u8],
+	offset: usize,
+) -> Result<&[u8], String>
+{
+	let rest: &[u8] = content.get(offset..).unwrap_or_default();
+	memchr::memchr(0, rest)
+		.map(|length: usize| &rest[..length])
+		.ok_or_else(|| format!("unterminated path at byte {}", offset))
+}
+
+/// Git's variable-length integer, in which each continuation also adds one, and how many bytes it took.
+fn read_varint(
+	content: &[u8],
+	offset: usize,
+) -> Result<(usize, usize), String>
+{
+	let mut length: usize = 0;
+	let mut value: usize = 0;
+	loop
+	{
+		let byte: u8 = *content
+			.get(offset + length)
+			.ok_or_else(|| format!("truncated at byte {}", offset + length))?;
+		if length > 0
+		{
+			value = (value + 1) << 7;
+		}
+		value |= (byte & 0x7f) as usize;
+		length += 1;
+		if byte & 0x80 == 0
+		{
+			return Result::Ok((value, length));
+		}
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::io::git_index::GitIndex;
+	use crate::hilcode::io::git_index::GitIndexEntry;
+	use crate::hilcode::test::env::TestEnv;
+
+	/// An index file of the given version listing `entries`, each a mode, stage and path.
+	fn index(
+		version: u32,
+		entries: &[(u32, u16, &str)],
+	) -> Vec<u8>
+	{
+		let mut content: Vec<u8> = b"DIRC".to_vec();
+		content.extend(version.to_be_bytes());
+		content.extend((entries.len() as u32).to_be_bytes());
+		let mut previous_path: &str = "";
+		for (mode, stage, path) in entries
+		{
+			let start: usize = content.len();
+			content.extend([0u8; 24]);
+			content.extend(mode.to_be_bytes());
+			content.extend([0u8; 32]);
+			content.extend(((stage << 12) | path.len().min(0xfff) as u16).to_be_bytes());
+			if version == 4
+			{
+				let common: usize = previous_path
+					.bytes()
+					.zip(path.bytes())
+					.take_while(|(lhs, rhs)| lhs == rhs)
+					.count();
+				assert!(previous_path.len() - common < 0x80);
+				content.push((previous_path.len() - common) as u8);
+				content.extend(&path.as_bytes()[common..]);
+				content.push(0);
+			}
+			else
+			{
+				content.extend(path.as_bytes());
+				content.extend(vec![0u8; 8 - (content.len() - start) % 8]);
+			}
+			previous_path = path;
+		}
+		content.extend(b"TREE\0\0\0\0");
+		content.extend([0u8; 20]);
+		content
+	}
+
+	fn paths(entries: &[GitIndexEntry]) -> Vec<&Path>
+	{
+		entries.iter().map(GitIndexEntry::path).collect()
+	}
+
+	#[test]
+	fn versions()
+	{
+		let entries: [(u32, u16, &str); 4] = [
+			(0o100644, 0, "Cargo.toml"),
+			(0o100755, 0, "src/bin/tool.rs"),
+			(0o040000, 0, "src/deep/"),
+			(0o120000, 0, "src/main.rs"),
+		];
+		for version in 2..=4
+		{
+			let parsed: Vec<GitIndexEntry> = GitIndex::parse(&index(version, &entries)).unwrap();
+			assert_eq!(
+				vec![
+					Path::new("Cargo.toml"),
+					Path::new("src/bin/tool.rs"),
+					Path::new("src/main.rs")
+				],
+				paths(&parsed)
+			);
+			assert_eq!(0o100755, parsed[1].mode());
+		}
+		assert!(GitIndex::parse(b"DIRC\0\0\0\x05\0\0\0\0").is_err());
+		assert!(GitIndex::parse(&index(2, &entries)[..40]).is_err());
+		assert!(GitIndex::parse(b"TREE").is_err());
+	}
+
+	#[test]
+	fn files_below()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::create_dir_all(root_directory.join(".git")).unwrap();
+		std::fs::create_dir_all(root_directory.join("src")).unwrap();
+		let content: Vec<u8> = index(
+			2,
+			&[
+				(0o100644, 0, "README.md"),
+				(0o100644, 1, "src/lib.rs"),
+				(0o100644, 2, "src/lib.rs"),
+				(0o100644, 0, "src/main.rs"),
+			],
+		);
+		std::fs::write(root_directory.join(".git/index"), content).unwrap();
+		let git_index: GitIndex = GitIndex::load(&root_directory.join("src")).unwrap();
+		assert_eq!(2, git_index.entries()[2].stage());
+		assert_eq!(
+			vec![PathBuf::from("lib.rs"), PathBuf::from("main.rs")],
+			git_index.files_below(&root_directory.join("src")).unwrap()
+		);
+		assert_eq!(3, git_index.files_below(root_directory).unwrap().len());
+	}
+
+	#[test]
+	fn worktree()
+	{
+		let test_env: TestEnv = TestEnv::default();
+		let root_directory: &Path = test_env.root_directory();
+		std::fs::create_dir_all(root_directory.join("main/.git/worktrees/feature")).unwrap();
+		std::fs::create_dir_all(root_directory.join("feature")).unwrap();
+		std::fs::write(root_directory.join("feature/.git"), "gitdir: ../main/.git/worktrees/feature\n").unwrap();
+		std::fs::write(root_directory.join("main/.git/worktrees/feature/index"), index(4, &[(0o100644, 0, "lib.rs")]))
+			.unwrap();
+		let git_index: GitIndex = GitIndex::load(&root_directory.join("feature")).unwrap();
+		assert_eq!(vec![Path::new("lib.rs")], paths(git_index.entries()));
+	}
+}
@@ -1,2 +1,11 @@
+pub mod content_filter;
+pub mod file_filter;
+pub mod file_list;
 pub mod file_set;
+pub mod file_set_expression;
 pub mod file_set_glob;
+pub mod file_set_registry;
+pub mod file_stat;
+pub mod git_ignore;
+pub mod git_index;
+pub mod normal_path;
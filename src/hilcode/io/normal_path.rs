@@ -0,0 +1,108 @@
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// `path` without `.` components and with every `..` that follows a normal component resolved against it.
+///
+/// This is purely lexical: symbolic links are not consulted, so `a/link/..` becomes `a` even if `link` points
+/// elsewhere. A relative path keeps the `..` components it starts with, and `..` right below the root stays at the
+/// root.
+pub fn normalise(path: &Path) -> PathBuf
+{
+	let mut components: Vec<Component> = Vec::new();
+	for component in path.components()
+	{
+		match component
+		{
+			Component::CurDir => (),
+			Component::ParentDir =>
+			{
+				match components.last()
+				{
+					Option::Some(Component::Normal(_)) =>
+					{
+						components.pop();
+					}
+					Option::Some(Component::RootDir) | Option::Some(Component::Prefix(_)) => (),
+					_ => components.push(component),
+				}
+			}
+			component => components.push(component),
+		}
+	}
+	if components.is_empty()
+	{
+		return PathBuf::from(".");
+	}
+	components.iter().collect()
+}
+
+/// The normalised, absolute form of `path`, taking relative paths from the current directory.
+pub fn absolute(path: &Path) -> PathBuf
+{
+	normalise(&std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()))
+}
+
+/// `path` relative to `base`, using `..` where `path` is not below `base`; both should be absolute and normalised.
+pub fn relative_to(
+	path: &Path,
+	base: &Path,
+) -> PathBuf
+{
+	let path_components: Vec<Component> = path.components().collect();
+	let base_components: Vec<Component> = base.components().collect();
+	let common: usize = path_components
+		.iter()
+		.zip(base_components.iter())
+		.take_while(|(lhs, rhs)| lhs == rhs)
+		.count();
+	let mut relative_path: PathBuf = PathBuf::new();
+	for _ in common..base_components.len()
+	{
+		relative_path.push(Component::ParentDir);
+	}
+	for component in &path_components[common..]
+	{
+		relative_path.push(component);
+	}
+	relative_path
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+	use std::path::PathBuf;
+
+	use crate::hilcode::io::normal_path::absolute;
+	use crate::hilcode::io::normal_path::normalise;
+	use crate::hilcode::io::normal_path::relative_to;
+
+	#[test]
+	fn normalise_paths()
+	{
+		assert_eq!(PathBuf::from("../teng/src/main.rs"), normalise(Path::new("./../teng/./src/bin/../main.rs")));
+		assert_eq!(PathBuf::from("/teng"), normalise(Path::new("/../teng/")));
+		assert_eq!(PathBuf::from("../.."), normalise(Path::new("a/../../..")));
+		assert_eq!(PathBuf::from("."), normalise(Path::new("a/..")));
+	}
+
+	#[test]
+	fn absolute_paths()
+	{
+		let current_directory: PathBuf = std::env::current_dir().unwrap();
+		assert_eq!(current_directory.join("src"), absolute(Path::new("./src/../src/.")));
+		assert_eq!(PathBuf::from("/usr/lib"), absolute(Path::new("/usr/bin/../lib")));
+	}
+
+	#[test]
+	fn relative_paths()
+	{
+		let base: &Path = Path::new("/work/project");
+		assert_eq!(PathBuf::from("src/main.rs"), relative_to(Path::new("/work/project/src/main.rs"), base));
+		assert_eq!(PathBuf::from("../teng/lib.rs"), relative_to(Path::new("/work/teng/lib.rs"), base));
+		assert_eq!(PathBuf::from(""), relative_to(base, base));
+	}
+}
@@ -1,17 +1,21 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
 
 use colored::ColoredString;
 use colored::Colorize;
 use time::OffsetDateTime;
-use time::format_description::BorrowedFormatItem;
+use time::UtcOffset;
+use time::format_description::OwnedFormatItem;
 
 use crate::hilcode::config::app_config::AppConfig;
 use crate::hilcode::log::level::LogLevel;
 #[cfg(test)]
 use crate::hilcode::test::utils::clear_escape_codes;
 
-pub trait Logger: std::io::Write
+/// Where log messages go; it is shared between threads behind a lock, so that messages never interleave.
+pub trait Logger: std::io::Write + Send
 {
 	#[cfg(test)]
 	fn log_messages(&self) -> Vec<String>;
@@ -55,12 +59,28 @@ pub trait HasLogger
 	);
 }
 
-fn get_local_time(time_format: &[BorrowedFormatItem<'_>]) -> ColoredString
+/// The current time at `time_offset` in `time_format`, followed by a space unless there is no time to show.
+fn get_local_time(
+	time_format: &OwnedFormatItem,
+	time_offset: Option<UtcOffset>,
+) -> ColoredString
 {
-	OffsetDateTime::now_local().unwrap().format(time_format).unwrap().cyan()
+	let local_time: String = time_offset
+		.and_then(|time_offset: UtcOffset| {
+			OffsetDateTime::now_utc()
+				.to_offset(time_offset)
+				.format(time_format)
+				.ok()
+		})
+		.unwrap_or_default();
+	if local_time.is_empty()
+	{
+		return local_time.normal();
+	}
+	format!("{} ", local_time).cyan()
 }
 
-pub struct AppLogger(Rc<AppConfig>, Rc<RefCell<dyn Logger>>);
+pub struct AppLogger(Arc<AppConfig>, Arc<Mutex<dyn Logger>>);
 
 pub struct StdOut(std::io::Stdout);
 
@@ -94,17 +114,28 @@ impl Logger for StdOut
 impl AppLogger
 {
 	#[cfg(not(test))]
-	pub fn new(app_config: Rc<AppConfig>) -> Rc<AppLogger>
+	pub fn new(app_config: Arc<AppConfig>) -> Arc<AppLogger>
 	{
-		Rc::new(AppLogger(app_config, Rc::new(RefCell::new(StdOut(std::io::stdout())))))
+		Arc::new(AppLogger(app_config, Arc::new(Mutex::new(StdOut(std::io::stdout())))))
 	}
 
 	#[cfg(test)]
-	pub fn new(app_config: Rc<AppConfig>) -> Rc<AppLogger>
+	pub fn new(app_config: Arc<AppConfig>) -> Arc<AppLogger>
 	{
-		let log_messages: Rc<RefCell<LogMessages>> = LogMessages::new();
+		let log_messages: Arc<Mutex<LogMessages>> = LogMessages::new();
 		AppLogger::test_logger(app_config, log_messages)
 	}
+
+	/// Writes `text` in one go. A thread that panicked while holding the lock leaves the logger as usable as before,
+	/// and a message that cannot be written, for instance because standard output was closed, is dropped.
+	fn write(
+		&self,
+		text: &str,
+	)
+	{
+		let mut logger: MutexGuard<dyn Logger> = self.1.lock().unwrap_or_else(PoisonError::into_inner);
+		let _ = logger.write_all(text.as_bytes());
+	}
 }
 
 impl HasLogger for AppLogger
@@ -122,7 +153,7 @@ impl HasLogger for AppLogger
 		message: &str,
 	)
 	{
-		write!(self.1.borrow_mut(), "{}", message).unwrap();
+		self.write(message);
 	}
 
 	fn trace(
@@ -130,10 +161,10 @@ impl HasLogger for AppLogger
 		message: &str,
 	)
 	{
-		let local_time: ColoredString = get_local_time(self.0.time_format());
+		let local_time: ColoredString = get_local_time(self.0.time_format(), self.0.time_offset());
 		let message: ColoredString = message.white();
 		let text: String = format!("{}{} {}\n", &local_time, "TRACE".normal(), message);
-		write!(self.1.borrow_mut(), "{}", text).unwrap();
+		self.write(&text);
 	}
 
 	fn debug(
@@ -141,10 +172,10 @@ impl HasLogger for AppLogger
 		message: &str,
 	)
 	{
-		let local_time: ColoredString = get_local_time(self.0.time_format());
+		let local_time: ColoredString = get_local_time(self.0.time_format(), self.0.time_offset());
 		let message: ColoredString = message.white();
 		let text: String = format!("{}{} {}\n", &local_time, "DEBUG".purple(), message);
-		write!(self.1.borrow_mut(), "{}", text).unwrap();
+		self.write(&text);
 	}
 
 	fn info(
@@ -152,10 +183,10 @@ impl HasLogger for AppLogger
 		message: &str,
 	)
 	{
-		let local_time: ColoredString = get_local_time(self.0.time_format());
+		let local_time: ColoredString = get_local_time(self.0.time_format(), self.0.time_offset());
 		let message: ColoredString = message.white();
 		let text: String = format!("{}{} {}\n", &local_time, "INFO ".cyan(), message);
-		write!(self.1.borrow_mut(), "{}", text).unwrap();
+		self.write(&text);
 	}
 
 	fn warn(
@@ -163,10 +194,10 @@ impl HasLogger for AppLogger
 		message: &str,
 	)
 	{
-		let local_time: ColoredString = get_local_time(self.0.time_format());
+		let local_time: ColoredString = get_local_time(self.0.time_format(), self.0.time_offset());
 		let message: ColoredString = message.white();
 		let text: String = format!("{}{} {}\n", &local_time, "WARN ".yellow(), message);
-		write!(self.1.borrow_mut(), "{}", text).unwrap();
+		self.write(&text);
 	}
 
 	fn error(
@@ -174,10 +205,10 @@ impl HasLogger for AppLogger
 		message: &str,
 	)
 	{
-		let local_time: ColoredString = get_local_time(self.0.time_format());
+		let local_time: ColoredString = get_local_time(self.0.time_format(), self.0.time_offset());
 		let message: ColoredString = message.white();
 		let text: String = format!("{}{} {}\n", &local_time, "ERROR".red(), message);
-		write!(self.1.borrow_mut(), "{}", text).unwrap();
+		self.write(&text);
 	}
 }
 
@@ -186,18 +217,42 @@ impl HasLogger for AppLogger
 mod tests
 {
 
-	use std::rc::Rc;
+	use std::sync::Arc;
+	use std::sync::MutexGuard;
+	use std::thread::JoinHandle;
+
+	use time::macros::format_description;
 
 	use crate::hilcode::config::app_config::AppConfig;
 	use crate::hilcode::log::has_logger::AppLogger;
 	use crate::hilcode::log::has_logger::HasLogger;
+	use crate::hilcode::log::has_logger::Logger;
 	use crate::hilcode::log::level::LogLevel;
+	use crate::hilcode::log::time_zone::TimeZone;
+
+	#[test]
+	fn time_zones()
+	{
+		for (time_zone, expected) in [
+			(TimeZone::Utc, "+00:00 INFO  The message\n"),
+			(TimeZone::None, "INFO  The message\n"),
+		]
+		{
+			let app_config: Arc<AppConfig> = AppConfig::builder()
+				.with_time_format(format_description!("[offset_hour sign:mandatory]:[offset_minute]"))
+				.with_time_zone(time_zone)
+				.build();
+			let app_logger: Arc<AppLogger> = AppLogger::new(app_config);
+			app_logger.info("The message");
+			assert_eq!(vec![expected.to_string()], app_logger.log_messages());
+		}
+	}
 
 	#[test]
 	fn stdout()
 	{
-		let app_config: Rc<AppConfig> = Rc::new(AppConfig::default());
-		let app_logger: Rc<AppLogger> = AppLogger::new(app_config);
+		let app_config: Arc<AppConfig> = Arc::new(AppConfig::default());
+		let app_logger: Arc<AppLogger> = AppLogger::new(app_config);
 		app_logger.stdout("The message");
 		let log_messages: Vec<String> = app_logger.log_messages();
 		assert_eq!(1, log_messages.len());
@@ -206,10 +261,55 @@ mod tests
 		assert_eq!(expected, actual);
 	}
 
+	#[test]
+	fn threads()
+	{
+		let app_logger: Arc<AppLogger> = enable_logging(LogLevel::Info);
+		let threads: Vec<JoinHandle<()>> = (0..4)
+			.map(|thread: usize| {
+				let app_logger: Arc<AppLogger> = Arc::clone(&app_logger);
+				std::thread::spawn(move || {
+					for index in 0..25
+					{
+						app_logger.info(&format!("thread {} message {}", thread, index));
+					}
+				})
+			})
+			.collect();
+		for thread in threads
+		{
+			thread.join().unwrap();
+		}
+		let log_messages: Vec<String> = app_logger.log_messages();
+		assert_eq!(100, log_messages.len());
+		assert!(
+			log_messages
+				.iter()
+				.all(|message: &String| message.starts_with("INFO  thread ") && message.ends_with('\n'))
+		);
+	}
+
+	#[test]
+	fn poisoned_lock()
+	{
+		let app_logger: Arc<AppLogger> = enable_logging(LogLevel::Info);
+		let poisoner: Arc<AppLogger> = Arc::clone(&app_logger);
+		let result: std::thread::Result<()> = std::thread::spawn(move || {
+			let _logger: MutexGuard<dyn Logger> = poisoner.1.lock().unwrap();
+			panic!("panicking while holding the logger");
+		})
+		.join();
+		assert!(result.is_err());
+		assert!(app_logger.1.is_poisoned());
+		app_logger.info("Still logging");
+		let log_messages: Vec<String> = app_logger.log_messages();
+		assert_eq!(vec!["INFO  Still logging\n".to_string()], log_messages);
+	}
+
 	mod trace
 	{
 
-		use std::rc::Rc;
+		use std::sync::Arc;
 
 		use crate::hilcode::log::has_logger::AppLogger;
 		use crate::hilcode::log::has_logger::tests::enable_logging;
@@ -220,7 +320,7 @@ mod tests
 		#[test]
 		fn off()
 		{
-			let app_logger: Rc<AppLogger> = no_logging();
+			let app_logger: Arc<AppLogger> = no_logging();
 			log_trace!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(0, log_messages.len());
@@ -229,7 +329,7 @@ mod tests
 		#[test]
 		fn on()
 		{
-			let app_logger: Rc<AppLogger> = enable_logging(LogLevel::Trace);
+			let app_logger: Arc<AppLogger> = enable_logging(LogLevel::Trace);
 			log_trace!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(1, log_messages.len());
@@ -242,7 +342,7 @@ mod tests
 	mod debug
 	{
 
-		use std::rc::Rc;
+		use std::sync::Arc;
 
 		use crate::hilcode::log::has_logger::AppLogger;
 		use crate::hilcode::log::has_logger::tests::enable_logging;
@@ -253,7 +353,7 @@ mod tests
 		#[test]
 		fn off()
 		{
-			let app_logger: Rc<AppLogger> = no_logging();
+			let app_logger: Arc<AppLogger> = no_logging();
 			log_debug!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(0, log_messages.len());
@@ -262,7 +362,7 @@ mod tests
 		#[test]
 		fn on()
 		{
-			let app_logger: Rc<AppLogger> = enable_logging(LogLevel::Debug);
+			let app_logger: Arc<AppLogger> = enable_logging(LogLevel::Debug);
 			log_debug!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(1, log_messages.len());
@@ -275,7 +375,7 @@ mod tests
 	mod info
 	{
 
-		use std::rc::Rc;
+		use std::sync::Arc;
 
 		use crate::hilcode::log::has_logger::AppLogger;
 		use crate::hilcode::log::has_logger::tests::enable_logging;
@@ -286,7 +386,7 @@ mod tests
 		#[test]
 		fn off()
 		{
-			let app_logger: Rc<AppLogger> = no_logging();
+			let app_logger: Arc<AppLogger> = no_logging();
 			log_info!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(0, log_messages.len());
@@ -295,7 +395,7 @@ mod tests
 		#[test]
 		fn on()
 		{
-			let app_logger: Rc<AppLogger> = enable_logging(LogLevel::Info);
+			let app_logger: Arc<AppLogger> = enable_logging(LogLevel::Info);
 			log_info!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(1, log_messages.len());
@@ -308,7 +408,7 @@ mod tests
 	mod warn
 	{
 
-		use std::rc::Rc;
+		use std::sync::Arc;
 
 		use crate::hilcode::log::has_logger::AppLogger;
 		use crate::hilcode::log::has_logger::tests::enable_logging;
@@ -319,7 +419,7 @@ mod tests
 		#[test]
 		fn off()
 		{
-			let app_logger: Rc<AppLogger> = no_logging();
+			let app_logger: Arc<AppLogger> = no_logging();
 			log_warn!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(0, log_messages.len());
@@ -328,7 +428,7 @@ mod tests
 		#[test]
 		fn on()
 		{
-			let app_logger: Rc<AppLogger> = enable_logging(LogLevel::Warn);
+			let app_logger: Arc<AppLogger> = enable_logging(LogLevel::Warn);
 			log_warn!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(1, log_messages.len());
@@ -341,7 +441,7 @@ mod tests
 	mod error
 	{
 
-		use std::rc::Rc;
+		use std::sync::Arc;
 
 		use crate::hilcode::log::has_logger::AppLogger;
 		use crate::hilcode::log::has_logger::tests::enable_logging;
@@ -352,7 +452,7 @@ mod tests
 		#[test]
 		fn off()
 		{
-			let app_logger: Rc<AppLogger> = no_logging();
+			let app_logger: Arc<AppLogger> = no_logging();
 			log_error!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(0, log_messages.len());
@@ -361,7 +461,7 @@ mod tests
 		#[test]
 		fn on()
 		{
-			let app_logger: Rc<AppLogger> = enable_logging(LogLevel::Error);
+			let app_logger: Arc<AppLogger> = enable_logging(LogLevel::Error);
 			log_error!(app_logger, "the message");
 			let log_messages: Vec<String> = app_logger.log_messages();
 			assert_eq!(1, log_messages.len());
@@ -371,15 +471,15 @@ mod tests
 		}
 	}
 
-	fn no_logging() -> Rc<AppLogger>
+	fn no_logging() -> Arc<AppLogger>
 	{
-		let app_config: Rc<AppConfig> = AppConfig::builder().without_logging().build();
+		let app_config: Arc<AppConfig> = AppConfig::builder().without_logging().build();
 		AppLogger::new(app_config)
 	}
 
-	fn enable_logging(log_level: LogLevel) -> Rc<AppLogger>
+	fn enable_logging(log_level: LogLevel) -> Arc<AppLogger>
 	{
-		let app_config: Rc<AppConfig> = AppConfig::builder().with_log_level(log_level).build();
+		let app_config: Arc<AppConfig> = AppConfig::builder().with_log_level(log_level).build();
 		AppLogger::new(app_config)
 	}
 }
@@ -391,9 +491,9 @@ pub struct LogMessages(pub Vec<String>);
 #[cfg(test)]
 impl LogMessages
 {
-	pub fn new() -> Rc<RefCell<LogMessages>>
+	pub fn new() -> Arc<Mutex<LogMessages>>
 	{
-		Rc::new(RefCell::new(LogMessages(Vec::new())))
+		Arc::new(Mutex::new(LogMessages(Vec::new())))
 	}
 }
 
@@ -431,15 +531,15 @@ impl Logger for LogMessages
 impl AppLogger
 {
 	pub fn test_logger<'test>(
-		app_config: Rc<AppConfig>,
-		log_messages: Rc<RefCell<LogMessages>>,
-	) -> Rc<AppLogger>
+		app_config: Arc<AppConfig>,
+		log_messages: Arc<Mutex<LogMessages>>,
+	) -> Arc<AppLogger>
 	{
-		Rc::new(AppLogger(app_config, log_messages))
+		Arc::new(AppLogger(app_config, log_messages))
 	}
 
 	pub fn log_messages(&self) -> Vec<String>
 	{
-		self.1.borrow().log_messages()
+		self.1.lock().unwrap_or_else(PoisonError::into_inner).log_messages()
 	}
 }
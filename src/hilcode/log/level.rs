@@ -1,3 +1,6 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub enum LogLevel
 {
@@ -46,6 +49,45 @@ impl LogLevel
 	}
 }
 
+impl FromStr for LogLevel
+{
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err>
+	{
+		match text.to_ascii_lowercase().as_str()
+		{
+			"off" => Result::Ok(LogLevel::Off),
+			"error" => Result::Ok(LogLevel::Error),
+			"warn" | "warning" => Result::Ok(LogLevel::Warn),
+			"info" => Result::Ok(LogLevel::Info),
+			"debug" => Result::Ok(LogLevel::Debug),
+			"trace" => Result::Ok(LogLevel::Trace),
+			_ => Result::Err(format!("expected off, error, warn, info, debug or trace, not '{}'", text)),
+		}
+	}
+}
+
+impl Display for LogLevel
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		let name: &str = match self
+		{
+			LogLevel::Off => "off",
+			LogLevel::Error => "error",
+			LogLevel::Warn => "warn",
+			LogLevel::Info => "info",
+			LogLevel::Debug => "debug",
+			LogLevel::Trace => "trace",
+		};
+		formatter.write_str(name)
+	}
+}
+
 #[cfg(test)]
 mod tests
 {
@@ -100,4 +142,22 @@ mod tests
 		assert_eq!(LogLevel::Debug.decrease(), LogLevel::Info);
 		assert_eq!(LogLevel::Trace.decrease(), LogLevel::Debug);
 	}
+
+	#[test]
+	fn names()
+	{
+		for log_level in [
+			LogLevel::Off,
+			LogLevel::Error,
+			LogLevel::Warn,
+			LogLevel::Info,
+			LogLevel::Debug,
+			LogLevel::Trace,
+		]
+		{
+			assert_eq!(Result::Ok(log_level), log_level.to_string().parse());
+		}
+		assert_eq!(Result::Ok(LogLevel::Warn), "WARNING".parse());
+		assert!("loud".parse::<LogLevel>().is_err());
+	}
 }
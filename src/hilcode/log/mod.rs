@@ -1,3 +1,4 @@
 pub mod has_logger;
 pub mod level;
 pub mod macros;
+pub mod time_zone;
@@ -0,0 +1,104 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use time::UtcOffset;
+
+/// Which clock the time in front of log messages shows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeZone
+{
+	Local,
+	Utc,
+	/// No time at all.
+	None,
+}
+
+impl TimeZone
+{
+	/// The offset to show times at, or `None` for no time.
+	///
+	/// The local offset is looked up now rather than for every message, because the lookup fails once a process has
+	/// several threads; when it cannot be determined at all, times are shown in UTC.
+	pub fn offset(self) -> Option<UtcOffset>
+	{
+		match self
+		{
+			TimeZone::Local => Option::Some(UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC)),
+			TimeZone::Utc => Option::Some(UtcOffset::UTC),
+			TimeZone::None => Option::None,
+		}
+	}
+}
+
+impl FromStr for TimeZone
+{
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err>
+	{
+		match text.to_ascii_lowercase().as_str()
+		{
+			"local" => Result::Ok(TimeZone::Local),
+			"utc" => Result::Ok(TimeZone::Utc),
+			"none" => Result::Ok(TimeZone::None),
+			_ => Result::Err(format!("expected local, utc or none, not '{}'", text)),
+		}
+	}
+}
+
+impl Display for TimeZone
+{
+	fn fmt(
+		&self,
+		formatter: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result
+	{
+		let name: &str = match self
+		{
+			TimeZone::Local => "local",
+			TimeZone::Utc => "utc",
+			TimeZone::None => "none",
+		};
+		formatter.write_str(name)
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use time::UtcOffset;
+
+	use crate::hilcode::log::time_zone::TimeZone;
+
+	#[test]
+	fn offsets()
+	{
+		assert_eq!(Option::Some(UtcOffset::UTC), TimeZone::Utc.offset());
+		assert_eq!(Option::None, TimeZone::None.offset());
+		assert_local_offset();
+		std::thread::spawn(assert_local_offset).join().unwrap();
+	}
+
+	/// The local offset where it can be determined, which it usually cannot once the process has several threads,
+	/// and UTC otherwise.
+	fn assert_local_offset()
+	{
+		match UtcOffset::current_local_offset()
+		{
+			Result::Ok(offset) => assert_eq!(Option::Some(offset), TimeZone::Local.offset()),
+			Result::Err(_) => assert_eq!(Option::Some(UtcOffset::UTC), TimeZone::Local.offset()),
+		}
+	}
+
+	#[test]
+	fn names()
+	{
+		for time_zone in [TimeZone::Local, TimeZone::Utc, TimeZone::None]
+		{
+			assert_eq!(Result::Ok(time_zone), time_zone.to_string().parse());
+		}
+		assert!("CET".parse::<TimeZone>().is_err());
+	}
+}
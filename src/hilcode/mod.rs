@@ -1,7 +1,10 @@
+pub mod build;
 pub mod config;
+pub mod daemon;
 pub mod error;
 pub mod io;
 pub mod log;
+pub mod parse;
 #[cfg(test)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub mod test;
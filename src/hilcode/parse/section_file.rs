@@ -0,0 +1,259 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::hilcode::error::hepheastus_error::HepheastusError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry
+{
+	key: String,
+	value: String,
+	line: usize,
+}
+
+impl Entry
+{
+	pub fn key(&self) -> &str
+	{
+		&self.key
+	}
+
+	pub fn value(&self) -> &str
+	{
+		&self.value
+	}
+
+	pub fn line(&self) -> usize
+	{
+		self.line
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Section
+{
+	kind: String,
+	name: Option<String>,
+	line: usize,
+	entries: Vec<Entry>,
+}
+
+impl Section
+{
+	pub fn kind(&self) -> &str
+	{
+		&self.kind
+	}
+
+	pub fn name(&self) -> Option<&str>
+	{
+		self.name.as_deref()
+	}
+
+	pub fn line(&self) -> usize
+	{
+		self.line
+	}
+
+	pub fn entries(&self) -> impl Iterator<Item = &Entry>
+	{
+		self.entries.iter()
+	}
+
+	pub fn get(
+		&self,
+		key: &str,
+	) -> Option<&Entry>
+	{
+		self.entries.iter().rev().find(|entry: &&Entry| entry.key == key)
+	}
+}
+
+/// A file made of `[kind name]` sections holding `key = value` entries.
+///
+/// Entries before the first section header belong to an anonymous section with an empty kind. Lines starting with
+/// `#` are comments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SectionFile
+{
+	path: PathBuf,
+	sections: Vec<Section>,
+}
+
+impl SectionFile
+{
+	pub fn load(path: &Path) -> Result<SectionFile, HepheastusError>
+	{
+		let text: String = std::fs::read_to_string(path)?;
+		SectionFile::parse(path, &text)
+	}
+
+	pub fn parse(
+		path: &Path,
+		text: &str,
+	) -> Result<SectionFile, HepheastusError>
+	{
+		let path: PathBuf = path.to_path_buf();
+		let mut sections: Vec<Section> = Vec::new();
+		let mut current: Section = Section {
+			kind: String::new(),
+			name: None,
+			line: 0,
+			entries: Vec::new(),
+		};
+		for (index, line) in text.lines().enumerate()
+		{
+			let line_number: usize = index + 1;
+			let line: &str = line.trim();
+			if line.is_empty() || line.starts_with('#')
+			{
+				continue;
+			}
+			if let Some(header) = line.strip_prefix('[')
+			{
+				let header: &str = header.strip_suffix(']').ok_or_else(|| {
+					HepheastusError::parse_error(&path, line_number, "section header is missing its closing ']'")
+				})?;
+				let mut words = header.split_whitespace();
+				let kind: String = words
+					.next()
+					.ok_or_else(|| HepheastusError::parse_error(&path, line_number, "section header is empty"))?
+					.to_string();
+				let name: Option<String> = words.next().map(str::to_string);
+				if words.next().is_some()
+				{
+					return Result::Err(HepheastusError::parse_error(
+						&path,
+						line_number,
+						"section header must be '[kind]' or '[kind name]'",
+					));
+				}
+				sections.push(current);
+				current = Section {
+					kind,
+					name,
+					line: line_number,
+					entries: Vec::new(),
+				};
+				continue;
+			}
+			let (key, value) = line
+				.split_once('=')
+				.ok_or_else(|| HepheastusError::parse_error(&path, line_number, "expected 'key = value'"))?;
+			let key: &str = key.trim();
+			if key.is_empty()
+			{
+				return Result::Err(HepheastusError::parse_error(&path, line_number, "missing key before '='"));
+			}
+			current.entries.push(Entry {
+				key: key.to_string(),
+				value: value.trim().to_string(),
+				line: line_number,
+			});
+		}
+		sections.push(current);
+		sections.retain(|section: &Section| !section.kind.is_empty() || !section.entries.is_empty());
+		Result::Ok(SectionFile { path, sections })
+	}
+
+	/// This file with every value replaced by what `map` makes of it, failing at the line of the first value it
+	/// rejects.
+	pub fn map_values(
+		mut self,
+		mut map: impl FnMut(&str) -> Result<String, String>,
+	) -> Result<SectionFile, HepheastusError>
+	{
+		for entry in self
+			.sections
+			.iter_mut()
+			.flat_map(|section: &mut Section| section.entries.iter_mut())
+		{
+			entry.value = map(&entry.value)
+				.map_err(|message: String| HepheastusError::parse_error(&self.path, entry.line, message))?;
+		}
+		Result::Ok(self)
+	}
+
+	pub fn path(&self) -> &Path
+	{
+		&self.path
+	}
+
+	pub fn sections(&self) -> impl Iterator<Item = &Section>
+	{
+		self.sections.iter()
+	}
+
+	pub fn sections_of_kind<'a>(
+		&'a self,
+		kind: &'a str,
+	) -> impl Iterator<Item = &'a Section>
+	{
+		self.sections
+			.iter()
+			.filter(move |section: &&Section| section.kind == kind)
+	}
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests
+{
+
+	use std::path::Path;
+
+	use crate::hilcode::error::hepheastus_error::HepheastusError;
+	use crate::hilcode::parse::section_file::Section;
+	use crate::hilcode::parse::section_file::SectionFile;
+
+	#[test]
+	fn parse()
+	{
+		let text: &str = "
+# a comment
+jobs = 4
+
+[pool link]
+depth = 2
+
+[log]
+level = debug
+";
+		let section_file: SectionFile = SectionFile::parse(Path::new("test.build"), text).unwrap();
+		let sections: Vec<&Section> = section_file.sections().collect();
+		assert_eq!(3, sections.len());
+		assert_eq!("", sections[0].kind());
+		assert_eq!("4", sections[0].get("jobs").unwrap().value());
+		assert_eq!("pool", sections[1].kind());
+		assert_eq!(Some("link"), sections[1].name());
+		assert_eq!(5, sections[1].line());
+		assert_eq!(6, sections[1].get("depth").unwrap().line());
+		assert_eq!("log", sections[2].kind());
+		assert_eq!(None, sections[2].name());
+		assert_eq!("debug", sections[2].get("level").unwrap().value());
+	}
+
+	#[test]
+	fn missing_equals()
+	{
+		let text: &str = "[pool link]\ndepth 2\n";
+		let error: HepheastusError = SectionFile::parse(Path::new("test.build"), text).unwrap_err();
+		match error
+		{
+			HepheastusError::ParseError { line, .. } => assert_eq!(2, line),
+			_ => panic!("unexpected error: {}", error),
+		}
+	}
+
+	#[test]
+	fn unterminated_header()
+	{
+		let text: &str = "[pool link\n";
+		let error: HepheastusError = SectionFile::parse(Path::new("test.build"), text).unwrap_err();
+		match error
+		{
+			HepheastusError::ParseError { line, .. } => assert_eq!(1, line),
+			_ => panic!("unexpected error: {}", error),
+		}
+	}
+}
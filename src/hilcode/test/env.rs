@@ -1,6 +1,6 @@
 use std::path::Path;
 use std::path::PathBuf;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tempfile::TempDir;
 use tempfile::tempdir;
@@ -10,7 +10,7 @@ use crate::hilcode::log::level::LogLevel;
 
 pub struct TestEnv
 {
-	app_config: Rc<AppConfig>,
+	app_config: Arc<AppConfig>,
 	temp_dir: TempDir,
 	root_directory: PathBuf,
 }
@@ -40,7 +40,7 @@ impl TestEnv
 	) -> TestEnv
 	{
 		let root_directory: PathBuf = root_directory.to_path_buf();
-		let app_config: Rc<AppConfig> = AppConfig::builder().with_log_level(LogLevel::Trace).build();
+		let app_config: Arc<AppConfig> = AppConfig::builder().with_log_level(LogLevel::Trace).build();
 		TestEnv {
 			app_config,
 			temp_dir,
@@ -4,151 +4,376 @@
 
 mod hilcode;
 
-use std::fmt::Debug;
-use std::fmt::Display;
-use std::fs::File;
-use std::fs::Metadata;
-use std::io::BufReader;
-use std::io::Error;
-use std::os::unix::fs::MetadataExt;
 use std::path::Path;
 use std::path::PathBuf;
-use std::rc::Rc;
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::Duration;
 
+use clap::CommandFactory;
 use clap::Parser;
-use time::OffsetDateTime;
-use time::macros::format_description;
-use xxhash_rust::xxh3::Xxh3;
+use clap_complete::CompleteEnv;
 
+use crate::hilcode::build::build_description::BUILD_FILE_NAME;
+use crate::hilcode::build::build_description::BuildDescription;
+use crate::hilcode::build::build_lock::BuildLock;
+use crate::hilcode::build::compile_commands::COMPILE_COMMANDS_FILE_NAME;
+use crate::hilcode::build::compile_commands::CompileCommands;
+use crate::hilcode::build::pool::PoolSet;
+use crate::hilcode::build::project::ProjectKind;
+use crate::hilcode::build::project::init_project;
 use crate::hilcode::config::app_config::AppConfig;
 use crate::hilcode::config::cli::Cli;
+use crate::hilcode::config::cli::Command;
+use crate::hilcode::config::cli::ConfigCommand;
+use crate::hilcode::config::completions::COMPLETE_VARIABLE;
+use crate::hilcode::config::completions::write_completions;
+use crate::hilcode::config::completions::write_man_page;
+use crate::hilcode::config::config_file::ConfigFile;
+use crate::hilcode::config::root_directory::root_directory;
+use crate::hilcode::config::settings::CONFIG_FILE_NAME;
+use crate::hilcode::config::settings::ConfigScope;
+use crate::hilcode::config::settings::SettingType;
+use crate::hilcode::config::settings::check_file;
+use crate::hilcode::config::settings::setting_key;
+use crate::hilcode::config::settings::validate;
+use crate::hilcode::daemon::client::DaemonClient;
+use crate::hilcode::daemon::server::DEFAULT_IDLE_TIMEOUT;
+use crate::hilcode::daemon::server::DaemonServer;
 use crate::hilcode::error::hepheastus_error::HepheastusError;
 use crate::hilcode::io::file_set::FileSet;
 use crate::hilcode::io::file_set_glob::FileSetGlob;
+use crate::hilcode::io::file_stat::FileStat;
+use crate::hilcode::parse::section_file::SectionFile;
 
 fn main()
 {
+	CompleteEnv::with_factory(Cli::command)
+		.var(COMPLETE_VARIABLE)
+		.complete();
 	let arguments: Vec<String> = std::env::args().collect();
 	let cli: Cli = Cli::parse_from(arguments);
-	let root_directory: PathBuf = PathBuf::new().join(".");
-	let app_config: Rc<AppConfig> = AppConfig::new(&root_directory, &cli);
-	println!("{:?}", app_config);
-	let base_directory: PathBuf = Path::new("../teng").to_path_buf();
-	let file_set_glob: FileSetGlob =
-		FileSetGlob::new(&base_directory, vec!["**/*.rs".into(), "!.git/**".into(), "!target/**".into()]);
-	let files: FileSet = file_set_glob.search("rust-sources").unwrap();
-	for (index, path_buf) in files.iter().enumerate()
+	match &cli.command
 	{
-		let file_stat: Result<FileStat, HepheastusError> = FileStat::get(path_buf);
-		file_stat
-			.iter()
-			.for_each(|file_stat: &FileStat| println!("{}: {:?} / {}", index, path_buf, &file_stat));
+		Option::Some(Command::CompileCommands { output }) =>
+		{
+			compile_commands(&load_app_config(&cli), output.as_deref())
+		}
+		Option::Some(Command::Completions { shell }) => or_exit(write_completions(*shell, &mut std::io::stdout())),
+		Option::Some(Command::Config { command }) =>
+		{
+			config(&or_exit(root_directory(cli.root.as_deref())), &cli, command)
+		}
+		Option::Some(Command::Daemon { idle_timeout, stop }) =>
+		{
+			daemon(&load_app_config(&cli), Duration::from_secs(*idle_timeout), *stop)
+		}
+		Option::Some(Command::Init { force }) => init(cli.root.as_deref(), *force),
+		Option::Some(Command::Man) => or_exit(write_man_page(&mut std::io::stdout())),
+		Option::None => build(&load_app_config(&cli), &cli),
 	}
-	println!("Okay");
 }
 
-#[derive(Debug)]
-pub struct Hash(u64);
+fn load_app_config(cli: &Cli) -> Arc<AppConfig>
+{
+	or_exit(
+		root_directory(cli.root.as_deref()).and_then(|root_directory: PathBuf| AppConfig::load(&root_directory, cli)),
+	)
+}
 
-impl Display for Hash
+fn build(
+	app_config: &AppConfig,
+	cli: &Cli,
+)
 {
-	fn fmt(
-		&self,
-		formatter: &mut std::fmt::Formatter<'_>,
-	) -> std::fmt::Result
+	println!("{:?}", app_config);
+	let build_description: BuildDescription =
+		or_exit(BuildDescription::load(app_config.root_directory(), app_config.variables()));
+	let target_names: Vec<String> = build_description.target_names(app_config.root_directory());
+	if let Option::Some(target) = cli
+		.targets
+		.iter()
+		.find(|target: &&String| !target_names.contains(target))
+	{
+		eprintln!("Unknown target: {}", target);
+		std::process::exit(1);
+	}
+	// Nothing schedules rules yet; creating the pools up front still rejects a target that asks for an undeclared one.
+	let _pool_set: PoolSet = or_exit(build_description.pool_set(app_config));
+	let build_lock: BuildLock = lock_build(app_config, cli.wait);
+	let base_directory: PathBuf = Path::new("../teng").to_path_buf();
+	let globs: Vec<String> = vec!["**/*.rs".into(), "!.git/**".into(), "!target/**".into()];
+	if cli.daemon
+	{
+		let mut daemon_client: DaemonClient = or_exit(DaemonClient::connect_or_start(
+			app_config.root_directory(),
+			&app_config.state_directory(),
+			DEFAULT_IDLE_TIMEOUT,
+		));
+		let files: FileSet = FileSet::new(
+			"rust-sources",
+			&base_directory,
+			or_exit(daemon_client.search("rust-sources", &base_directory, &globs)),
+		);
+		for (index, (path_buf, display_path)) in files.absolute_paths().zip(files.display_paths()).enumerate()
+		{
+			if let Result::Ok(file_stat) = daemon_client.stat(&path_buf)
+			{
+				println!("{}: {} / {}", index, display_path, &file_stat);
+			}
+		}
+	}
+	else
 	{
-		formatter.write_fmt(format_args!("{:16x}", self.0))
+		let file_set_glob: FileSetGlob = FileSetGlob::new(&base_directory, globs).with_threads(app_config.jobs());
+		let files: FileSet = file_set_glob.search("rust-sources").unwrap();
+		for (index, (path_buf, display_path)) in files.absolute_paths().zip(files.display_paths()).enumerate()
+		{
+			let file_stat: Result<FileStat, HepheastusError> = FileStat::get(&path_buf);
+			file_stat
+				.iter()
+				.for_each(|file_stat: &FileStat| println!("{}: {} / {}", index, display_path, &file_stat));
+		}
 	}
+	drop(build_lock);
+	println!("Okay");
 }
 
-#[derive(Debug)]
-pub struct FileSize(u64);
+fn compile_commands(
+	app_config: &AppConfig,
+	output: Option<&Path>,
+)
+{
+	let build_description: BuildDescription =
+		or_exit(BuildDescription::load(app_config.root_directory(), app_config.variables()));
+	let compile_commands: CompileCommands =
+		CompileCommands::from_build_graph(build_description.build_graph(), app_config.root_directory());
+	let output: PathBuf = output
+		.map(Path::to_path_buf)
+		.unwrap_or_else(|| app_config.root_directory().join(COMPILE_COMMANDS_FILE_NAME));
+	or_exit(compile_commands.write(&output));
+	println!("Wrote {} compile commands to {}", compile_commands.len(), output.display());
+}
 
-impl Display for FileSize
+/// The configuration commands that edit or check the configuration files work without loading the configuration,
+/// so that they can repair it.
+fn config(
+	root_directory: &Path,
+	cli: &Cli,
+	command: &ConfigCommand,
+)
 {
-	fn fmt(
-		&self,
-		formatter: &mut std::fmt::Formatter<'_>,
-	) -> std::fmt::Result
+	match command
 	{
-		formatter.write_fmt(format_args!("{}B", self.0))
+		ConfigCommand::Show { resolved: false } =>
+		{
+			let app_config: Arc<AppConfig> = or_exit(AppConfig::load(root_directory, cli));
+			for (key, setting) in app_config.settings().iter()
+			{
+				println!("{} = {}  # {}", key, setting.value(), setting.origin());
+			}
+		}
+		ConfigCommand::Show { resolved: true } =>
+		{
+			let app_config: Arc<AppConfig> = or_exit(AppConfig::load(root_directory, cli));
+			let (settings, variables) = or_exit(app_config.settings().resolve(app_config.root_directory()));
+			for (key, setting) in settings.iter()
+			{
+				println!("{} = {}  # {}", key, setting.value(), setting.origin());
+			}
+			for (name, value) in variables.iter()
+			{
+				println!("${{{}}} = {}  # {}", name, value, value.type_name());
+			}
+		}
+		ConfigCommand::Get { key } =>
+		{
+			let app_config: Arc<AppConfig> = or_exit(AppConfig::load(root_directory, cli));
+			match (app_config.settings().get(key), SettingType::of(key))
+			{
+				(Option::Some(setting), _) => println!("{}", setting.value()),
+				(Option::None, Result::Ok(_)) =>
+				{
+					eprintln!("{} is not set", key);
+					std::process::exit(1);
+				}
+				(Option::None, Result::Err(message)) =>
+				{
+					eprintln!("{}", message);
+					std::process::exit(1);
+				}
+			}
+		}
+		ConfigCommand::Set { key, value, scope } =>
+		{
+			or_exit(validate(key, value).map_err(|message: String| invalid_setting(key, value, message)));
+			let mut config_file: ConfigFile = or_exit(ConfigFile::load(&config_path(root_directory, *scope)));
+			or_exit(config_file.set(key, value).and_then(|_| config_file.save()));
+		}
+		ConfigCommand::Unset { key, scope } =>
+		{
+			let mut config_file: ConfigFile = or_exit(ConfigFile::load(&config_path(root_directory, *scope)));
+			if !or_exit(config_file.unset(key))
+			{
+				println!("{} is not set in {}", key, config_file.path().display());
+				return;
+			}
+			or_exit(config_file.save());
+		}
+		ConfigCommand::List { scope } =>
+		{
+			let scopes: Vec<ConfigScope> = scope.map_or(ConfigScope::ALL.to_vec(), |scope: ConfigScope| vec![scope]);
+			for path in scopes
+				.into_iter()
+				.filter_map(|scope: ConfigScope| scope.path(root_directory))
+				.filter(|path: &PathBuf| path.is_file())
+			{
+				let section_file: SectionFile = or_exit(SectionFile::load(&path));
+				for section in section_file.sections()
+				{
+					for entry in section.entries()
+					{
+						println!(
+							"{}:{}: {} = {}",
+							path.display(),
+							entry.line(),
+							setting_key(section, entry),
+							entry.value()
+						);
+					}
+				}
+			}
+		}
+		ConfigCommand::Validate =>
+		{
+			let mut errors: Vec<HepheastusError> = ConfigScope::ALL
+				.into_iter()
+				.filter_map(|scope: ConfigScope| scope.path(root_directory))
+				.filter(|path: &PathBuf| path.is_file())
+				.flat_map(|path: PathBuf| check_file(&path))
+				.collect();
+			if errors.is_empty()
+			{
+				errors.extend(AppConfig::load(root_directory, cli).err());
+			}
+			for error in &errors
+			{
+				eprintln!("{}", error);
+			}
+			if !errors.is_empty()
+			{
+				std::process::exit(1);
+			}
+			println!("The configuration is valid");
+		}
 	}
 }
 
-#[derive(Debug)]
-pub struct FileMode(u32);
-
-impl Display for FileMode
+fn config_path(
+	root_directory: &Path,
+	scope: ConfigScope,
+) -> PathBuf
 {
-	fn fmt(
-		&self,
-		formatter: &mut std::fmt::Formatter<'_>,
-	) -> std::fmt::Result
+	match scope.path(root_directory)
 	{
-		let permissions: [u8; 9] = [
-			if self.0 & 0o400 == 0 { b'-' } else { b'r' },
-			if self.0 & 0o200 == 0 { b'-' } else { b'w' },
-			if self.0 & 0o100 == 0 { b'-' } else { b'x' },
-			if self.0 & 0o040 == 0 { b'-' } else { b'r' },
-			if self.0 & 0o020 == 0 { b'-' } else { b'w' },
-			if self.0 & 0o010 == 0 { b'-' } else { b'x' },
-			if self.0 & 0o004 == 0 { b'-' } else { b'r' },
-			if self.0 & 0o002 == 0 { b'-' } else { b'w' },
-			if self.0 & 0o001 == 0 { b'-' } else { b'x' },
-		];
-		let permissions: &str = unsafe { std::str::from_utf8_unchecked(&permissions) };
-		formatter.write_fmt(format_args!("{}", permissions))
+		Option::Some(path) => path,
+		Option::None =>
+		{
+			eprintln!("There is no user configuration directory; set HOME or XDG_CONFIG_HOME");
+			std::process::exit(1);
+		}
 	}
 }
 
-#[derive(Debug)]
-pub struct FileStat
+fn invalid_setting(
+	key: &str,
+	value: &str,
+	message: String,
+) -> HepheastusError
 {
-	modified: SystemTime,
-	file_size: FileSize,
-	file_mode: FileMode,
-	hash: Hash,
+	HepheastusError::InvalidSetting {
+		key: key.to_string(),
+		value: value.to_string(),
+		message,
+	}
 }
 
-impl Display for FileStat
+fn daemon(
+	app_config: &AppConfig,
+	idle_timeout: Duration,
+	stop: bool,
+)
 {
-	fn fmt(
-		&self,
-		formatter: &mut std::fmt::Formatter<'_>,
-	) -> std::fmt::Result
+	let state_directory: PathBuf = app_config.state_directory();
+	if stop
 	{
-		let modified: OffsetDateTime = self.modified.into();
-		let format = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]");
-		let modified = modified.format(format).unwrap();
-		formatter.write_fmt(format_args!("{}|{}|{}|{}", &modified, &self.file_size, &self.file_mode, &self.hash))
+		match DaemonClient::connect(&state_directory)
+		{
+			Result::Ok(mut daemon_client) => or_exit(daemon_client.shutdown()),
+			Result::Err(_) => println!("No daemon is running"),
+		}
+		return;
 	}
+	let daemon_server: DaemonServer = DaemonServer::new(&state_directory, idle_timeout);
+	or_exit(daemon_server.run());
 }
 
-impl FileStat
+/// Sets up `directory`, or else the current directory, which need not be below a root directory yet.
+fn init(
+	directory: Option<&Path>,
+	force: bool,
+)
 {
-	pub fn get(path_buf: &PathBuf) -> Result<FileStat, HepheastusError>
+	let directory: PathBuf = match directory
 	{
-		let metadata: Metadata = path_buf.metadata()?;
-		let modified: SystemTime = metadata.modified()?;
-		let file_size: FileSize = FileSize(metadata.size());
-		let file_mode: FileMode = FileMode(metadata.mode());
-		let hash: Hash = FileStat::get_hash(path_buf)?;
-		let file_stat: FileStat = FileStat {
-			modified,
-			file_size,
-			file_mode,
-			hash,
-		};
-		Result::Ok(file_stat)
+		Option::Some(directory) => directory.to_path_buf(),
+		Option::None => or_exit(std::env::current_dir().map_err(HepheastusError::from)),
+	};
+	let project_kind: ProjectKind = or_exit(init_project(&directory, force));
+	println!(
+		"Wrote {} and {} for a {} project in {}",
+		BUILD_FILE_NAME,
+		CONFIG_FILE_NAME,
+		project_kind,
+		directory.display()
+	);
+}
+
+/// The value of `result`, or else exits after printing the error.
+fn or_exit<T>(result: Result<T, HepheastusError>) -> T
+{
+	match result
+	{
+		Result::Ok(value) => value,
+		Result::Err(error) =>
+		{
+			eprintln!("{}", error);
+			std::process::exit(1);
+		}
 	}
+}
 
-	fn get_hash(path_buf: &PathBuf) -> Result<Hash, Error>
+fn lock_build(
+	app_config: &AppConfig,
+	wait: bool,
+) -> BuildLock
+{
+	let state_directory: PathBuf = app_config.state_directory();
+	match BuildLock::try_acquire(&state_directory)
 	{
-		let file: File = File::open(path_buf)?;
-		let mut buf_reader: BufReader<File> = BufReader::new(file);
-		let mut hasher: Xxh3 = Xxh3::new();
-		std::io::copy(&mut buf_reader, &mut hasher)?;
-		Result::Ok(Hash(hasher.digest()))
+		Result::Ok(build_lock) => build_lock,
+		Result::Err(HepheastusError::BuildLocked { pid, .. }) if wait =>
+		{
+			match pid
+			{
+				Option::Some(pid) => println!("Waiting for another build (PID {}) to finish...", pid),
+				Option::None => println!("Waiting for another build to finish..."),
+			}
+			or_exit(BuildLock::acquire(&state_directory))
+		}
+		Result::Err(error) =>
+		{
+			eprintln!("{}", error);
+			std::process::exit(1);
+		}
 	}
 }